@@ -8,10 +8,19 @@ use sdf_shader::SDFShaderPlugin;
 
 pub mod utils;
 
+pub mod sdf_2d;
+pub mod sdf_grid;
+pub mod sdf_heightfield;
+pub mod sdf_mesh;
+pub mod sdf_modifiers;
+pub mod sdf_noise;
 pub mod sdf_object;
 pub mod sdf_operations;
+pub mod sdf_polyhedron;
 pub mod sdf_primitives;
 pub mod sdf_shader;
+pub mod sdf_spline;
+pub mod sdf_text;
 
 /// A plugin
 pub struct SDFPlugin;
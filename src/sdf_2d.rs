@@ -0,0 +1,239 @@
+//! Describes the available 2D SDF primitives, used to build extruded and revolved primitives
+
+use bevy::prelude::Vec2;
+
+/// The basic 2D primitives, on the XY plane
+#[derive(Debug, Clone, PartialEq)]
+pub enum SDFPrimitive2D {
+    /// Defines a circle
+    Circle {
+        /// The center of the circle
+        center: Vec2,
+        /// The radius of the circle
+        radius: f32,
+    },
+    /// Defines a rectangle
+    Rectangle {
+        /// The center of the rectangle
+        center: Vec2,
+        /// The half bounds of the rectangle
+        half_extents: Vec2,
+    },
+    /// Defines a polygon, provided it's closed rings of points
+    ///
+    /// Rings inside of other rings cut holes into the polygon
+    Polygon(Vec<Vec<Vec2>>),
+}
+
+impl SDFPrimitive2D {
+    /// Get the value of the SDF at a given point
+    pub fn value_at_point(&self, point: &Vec2) -> f32 {
+        let point = *point;
+        match self {
+            SDFPrimitive2D::Circle { center, radius } => (point - *center).length() - radius,
+            SDFPrimitive2D::Rectangle {
+                center,
+                half_extents,
+            } => rectangle_sdf(point - *center, *half_extents),
+            SDFPrimitive2D::Polygon(rings) => polygon_sdf(point, rings, false),
+        }
+    }
+
+    /// Get the value of the SDF at a given point, when revolved around the Y axis
+    ///
+    /// Polygon edges lying on the Y axis are ignored, since they are inside the revolved shape
+    pub fn revolved_value_at_point(&self, point: &Vec2) -> f32 {
+        match self {
+            SDFPrimitive2D::Polygon(rings) => polygon_sdf(*point, rings, true),
+            _ => self.value_at_point(point),
+        }
+    }
+
+    /// Get the bounds of the SDF
+    pub fn get_bounds(&self) -> (Vec2, Vec2) {
+        match self {
+            SDFPrimitive2D::Circle { center, radius } => (*center - *radius, *center + *radius),
+            SDFPrimitive2D::Rectangle {
+                center,
+                half_extents,
+            } => (*center - *half_extents, *center + *half_extents),
+            SDFPrimitive2D::Polygon(rings) => {
+                let bounds = rings.iter().flatten().fold(
+                    (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+                    |bounds, point| (bounds.0.min(*point), bounds.1.max(*point)),
+                );
+                if bounds.0.x > bounds.1.x {
+                    (Vec2::ZERO, Vec2::ZERO)
+                } else {
+                    bounds
+                }
+            }
+        }
+    }
+}
+
+fn rectangle_sdf(point: Vec2, half_extents: Vec2) -> f32 {
+    let q = point.abs() - half_extents;
+    q.max(Vec2::ZERO).length() + q.x.max(q.y).min(0.)
+}
+
+fn polygon_sdf(point: Vec2, rings: &[Vec<Vec2>], skip_axis: bool) -> f32 {
+    let mut distance = f32::INFINITY;
+    let mut sign = 1.;
+    for ring in rings.iter().filter(|ring| !ring.is_empty()) {
+        let mut previous = ring[ring.len() - 1];
+        for current in ring.iter() {
+            let edge = previous - *current;
+            let offset = point - *current;
+            let t = if edge.length_squared() > 0. {
+                (offset.dot(edge) / edge.length_squared()).clamp(0., 1.)
+            } else {
+                0.
+            };
+            if !(skip_axis && previous.x.abs() < 1e-6 && current.x.abs() < 1e-6) {
+                distance = distance.min((offset - edge * t).length_squared());
+            }
+
+            let crossings = [
+                point.y >= current.y,
+                point.y < previous.y,
+                edge.x * offset.y > edge.y * offset.x,
+            ];
+            if crossings.iter().all(|c| *c) || crossings.iter().all(|c| !*c) {
+                sign *= -1.;
+            }
+            previous = *current;
+        }
+    }
+    sign * distance.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use super::*;
+
+    fn square_with_hole() -> SDFPrimitive2D {
+        SDFPrimitive2D::Polygon(vec![
+            vec![
+                Vec2::new(-2., -2.),
+                Vec2::new(2., -2.),
+                Vec2::new(2., 2.),
+                Vec2::new(-2., 2.),
+            ],
+            vec![
+                Vec2::new(-1., -1.),
+                Vec2::new(-1., 1.),
+                Vec2::new(1., 1.),
+                Vec2::new(1., -1.),
+            ],
+        ])
+    }
+
+    #[test]
+    fn calculates_circle_sdf() {
+        let sdf = SDFPrimitive2D::Circle {
+            center: Vec2::X,
+            radius: 1.,
+        };
+
+        let interior = sdf.value_at_point(&Vec2::X);
+        let surface = sdf.value_at_point(&Vec2::ZERO);
+        let outside = sdf.value_at_point(&Vec2::new(1., 1.5));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculates_rectangle_sdf() {
+        let sdf = SDFPrimitive2D::Rectangle {
+            center: Vec2::Y,
+            half_extents: Vec2::new(1., 2.),
+        };
+
+        let interior = sdf.value_at_point(&Vec2::Y);
+        let surface = sdf.value_at_point(&(Vec2::Y * 3.));
+        let corner = sdf.value_at_point(&Vec2::new(4., 7.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(corner, 5.);
+    }
+
+    #[test]
+    fn calculates_triangle_sdf() {
+        let sdf = SDFPrimitive2D::Polygon(vec![vec![
+            Vec2::new(-1., 0.),
+            Vec2::new(1., 0.),
+            Vec2::new(0., 1.),
+        ]]);
+
+        let interior = sdf.value_at_point(&Vec2::new(0., 0.25));
+        let surface = sdf.value_at_point(&Vec2::new(0.5, 0.5));
+        let outside = sdf.value_at_point(&Vec2::new(0., -0.5));
+        let corner = sdf.value_at_point(&Vec2::new(0., 1.5));
+
+        assert_float_absolute_eq!(interior, -0.25);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+        assert_float_absolute_eq!(corner, 0.5);
+    }
+
+    #[test]
+    fn calculates_polygon_with_hole_sdf() {
+        let sdf = square_with_hole();
+
+        let hole = sdf.value_at_point(&Vec2::ZERO);
+        let interior = sdf.value_at_point(&Vec2::new(1.5, 0.));
+        let inner_surface = sdf.value_at_point(&Vec2::new(0., -1.));
+        let outside = sdf.value_at_point(&Vec2::new(-2.5, 0.));
+
+        assert_float_absolute_eq!(hole, 1.);
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(inner_surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculate_circle_bounds() {
+        let sdf = SDFPrimitive2D::Circle {
+            center: Vec2::new(1., -1.),
+            radius: 0.5,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, 0.5);
+        assert_float_absolute_eq!(bounds.0.y, -1.5);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, -0.5);
+    }
+
+    #[test]
+    fn calculate_rectangle_bounds() {
+        let sdf = SDFPrimitive2D::Rectangle {
+            center: Vec2::new(1., -1.),
+            half_extents: Vec2::new(0.5, 2.),
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, 0.5);
+        assert_float_absolute_eq!(bounds.0.y, -3.);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+    }
+
+    #[test]
+    fn calculate_polygon_bounds() {
+        let sdf = SDFPrimitive2D::Polygon(vec![vec![
+            Vec2::new(-1., 0.),
+            Vec2::new(2., 0.5),
+            Vec2::new(0., 3.),
+        ]]);
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, 0.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 3.);
+    }
+}
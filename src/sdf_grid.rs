@@ -0,0 +1,211 @@
+//! Signed distance fields sampled on a dense grid
+use bevy::prelude::*;
+
+use crate::sdf_object::SDFObject;
+
+/// A dense grid of pre-calculated distances
+///
+/// The grid is evaluated using trilinear interpolation. Outside of the grid, the value is at least
+/// the distance to the grid's box, or the value at the closest point on the box minus that distance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SDFGrid {
+    /// The position of the first sample
+    origin: Vec3,
+    /// The distance between samples
+    voxel_size: f32,
+    /// The number of samples along each axis
+    dimensions: UVec3,
+    /// The distances, ordered along X, then Y, then Z
+    values: Vec<f32>,
+}
+
+impl SDFGrid {
+    /// Create a grid from a list of distances, ordered along X, then Y, then Z
+    ///
+    /// Returns `None` if the number of values doesn't match the dimensions
+    pub fn new(origin: Vec3, voxel_size: f32, dimensions: UVec3, values: Vec<f32>) -> Option<Self> {
+        let count = sample_count(dimensions)?;
+        if count == 0 || values.len() != count || voxel_size <= 0. {
+            return None;
+        }
+        Some(Self {
+            origin,
+            voxel_size,
+            dimensions,
+            values,
+        })
+    }
+
+    /// Sample an SDF Object into a grid covering it's bake bounds, padded by a voxel
+    ///
+    /// Returns `None` if the voxel size isn't positive, or the number of samples overflows
+    pub fn from_object(object: &SDFObject, voxel_size: f32) -> Option<Self> {
+        if voxel_size <= 0. {
+            return None;
+        }
+        let (min, max) = object.get_bake_bounds();
+        let (min, max) = (min - voxel_size, max + voxel_size);
+        let dimensions = ((max - min) / voxel_size)
+            .ceil()
+            .as_uvec3()
+            .min(UVec3::splat(u32::MAX - 1))
+            + 1;
+        sample_count(dimensions)?;
+        let values = (0..dimensions.z)
+            .flat_map(|z| {
+                (0..dimensions.y)
+                    .flat_map(move |y| (0..dimensions.x).map(move |x| UVec3::new(x, y, z)))
+            })
+            .map(|index| object.value_at_point(&(min + index.as_vec3() * voxel_size)))
+            .collect();
+        Self::new(min, voxel_size, dimensions, values)
+    }
+
+    fn value(&self, x: u32, y: u32, z: u32) -> f32 {
+        let (width, height) = (self.dimensions.x as usize, self.dimensions.y as usize);
+        self.values[x as usize + width * (y as usize + height * z as usize)]
+    }
+
+    /// Get the value of the SDF at a given point
+    pub fn value_at_point(&self, point: Vec3) -> f32 {
+        let (min, max) = self.get_bounds();
+        let clamped = point.clamp(min, max);
+        let outside = point.distance(clamped);
+
+        let last = self.dimensions - 1;
+        let sample = (clamped - self.origin) / self.voxel_size;
+        let start = sample.floor().as_uvec3().min(last);
+        let end = (start + 1).min(last);
+        let t = sample - start.as_vec3();
+
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let along_x =
+            |y: u32, z: u32| lerp(self.value(start.x, y, z), self.value(end.x, y, z), t.x);
+        let near = lerp(along_x(start.y, start.z), along_x(end.y, start.z), t.y);
+        let far = lerp(along_x(start.y, end.z), along_x(end.y, end.z), t.y);
+        let value = lerp(near, far, t.z);
+        if outside > 0. {
+            outside.max(value - outside)
+        } else {
+            value
+        }
+    }
+
+    /// Get the bounds of the grid
+    pub fn get_bounds(&self) -> (Vec3, Vec3) {
+        (
+            self.origin,
+            self.origin + (self.dimensions - 1).as_vec3() * self.voxel_size,
+        )
+    }
+}
+
+/// The number of samples in a grid, if it fits in a `usize`
+fn sample_count(dimensions: UVec3) -> Option<usize> {
+    (dimensions.x as usize)
+        .checked_mul(dimensions.y as usize)?
+        .checked_mul(dimensions.z as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_float_eq::*;
+
+    use super::*;
+    use crate::{
+        sdf_object::SDFElement, sdf_operations::SDFOperators, sdf_primitives::SDFPrimitive,
+    };
+
+    fn sphere_grid() -> SDFGrid {
+        let object = SDFObject::default().with_element(SDFElement::default());
+        SDFGrid::from_object(&object, 0.25).unwrap()
+    }
+
+    #[test]
+    fn rejects_mismatched_values() {
+        assert!(SDFGrid::new(Vec3::ZERO, 1., UVec3::new(2, 2, 2), vec![0.; 7]).is_none());
+        assert!(SDFGrid::new(Vec3::ZERO, 1., UVec3::splat(u32::MAX), vec![]).is_none());
+    }
+
+    #[test]
+    fn rejects_overflowing_sample_counts() {
+        let object = SDFObject::default().with_element(SDFElement::default());
+
+        assert!(SDFGrid::from_object(&object, 1e-7).is_none());
+        assert!(SDFGrid::from_object(&object, 0.).is_none());
+    }
+
+    #[test]
+    fn interpolates_values() {
+        let values = (0..8).map(|i| (i % 2) as f32).collect();
+        let sdf = SDFGrid::new(Vec3::ZERO, 2., UVec3::new(2, 2, 2), values).unwrap();
+
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::ZERO), 0.);
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::X * 2.), 1.);
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::new(0.5, 1., 1.5)), 0.25);
+    }
+
+    #[test]
+    fn uses_distance_to_box_outside_of_grid() {
+        let values = (0..8).map(|i| (i % 2) as f32 * 5.).collect();
+        let sdf = SDFGrid::new(Vec3::ZERO, 2., UVec3::new(2, 2, 2), values).unwrap();
+
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::new(-1., 1., 1.)), 1.);
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::new(3., 1., 1.)), 4.);
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::new(5., 1., 1.)), 3.);
+    }
+
+    #[test]
+    fn never_overestimates_outside_of_grid() {
+        let sdf = sphere_grid();
+
+        for point in [
+            Vec3::new(3., 0.5, 0.),
+            Vec3::new(-2., 2., 1.),
+            Vec3::new(0.5, -1.5, 4.),
+        ] {
+            assert!(sdf.value_at_point(point) <= point.length() - 1.);
+        }
+    }
+
+    #[test]
+    fn sampled_grid_matches_object() {
+        let sdf = sphere_grid();
+
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::ZERO), -1.);
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::Y), 0.);
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::new(0.3, 0.6, 0.)), -0.33, 0.01);
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::new(3., 0., 0.)), 1.75);
+    }
+
+    #[test]
+    fn combines_grid_with_operators() {
+        let object = SDFObject::default()
+            .with_element(
+                SDFElement::default().with_primitive(SDFPrimitive::Grid(Arc::new(sphere_grid()))),
+            )
+            .with_element(
+                SDFElement::default()
+                    .with_primitive(SDFPrimitive::Box(Vec3::ONE))
+                    .with_translation(Vec3::Y * 1.5)
+                    .with_operation(SDFOperators::Subtraction),
+            );
+
+        assert_float_absolute_eq!(object.value_at_point(&Vec3::ZERO), -0.5);
+        assert_float_absolute_eq!(object.value_at_point(&(Vec3::Y * -0.5)), -0.5);
+    }
+
+    #[test]
+    fn calculates_grid_bounds() {
+        let sdf = SDFGrid::new(Vec3::ONE, 0.5, UVec3::new(3, 2, 5), vec![0.; 30]).unwrap();
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, 1.);
+        assert_float_absolute_eq!(bounds.0.y, 1.);
+        assert_float_absolute_eq!(bounds.0.z, 1.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 1.5);
+        assert_float_absolute_eq!(bounds.1.z, 3.);
+    }
+}
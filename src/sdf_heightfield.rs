@@ -0,0 +1,224 @@
+//! Signed distance fields built from height maps
+use bevy::{prelude::*, render::render_resource::TextureFormat};
+
+/// A terrain height map, prepared for signed distance queries
+///
+/// The height field covers the XZ plane centered on the origin, with the image's columns
+/// along the X axis and it's rows along the Z axis. The solid fills the space between
+/// `y = 0` and the sampled height.
+///
+/// The distance is divided by the maximum slope of the height field,
+/// so it's a conservative bound that never overestimates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SDFHeightfield {
+    /// The number of samples along the X axis
+    width: usize,
+    /// The number of samples along the Z axis
+    depth: usize,
+    /// The heights, in row order, already multiplied by the height scale
+    heights: Vec<f32>,
+    /// The size of the height field along the X and Z axes
+    extent: Vec2,
+    /// The highest point of the height field
+    max_height: f32,
+    /// The factor the height difference gets divided by to keep the distance a bound
+    lipschitz: f32,
+}
+
+impl SDFHeightfield {
+    /// Create a height field from a list of normalized heights, in row order
+    ///
+    /// Returns `None` if the number of heights doesn't match the dimensions
+    pub fn new(
+        width: usize,
+        depth: usize,
+        heights: &[f32],
+        extent: Vec2,
+        height_scale: f32,
+    ) -> Option<Self> {
+        if width == 0 || depth == 0 || heights.len() != width * depth {
+            return None;
+        }
+        let heights = heights
+            .iter()
+            .map(|height| height * height_scale)
+            .collect::<Vec<_>>();
+        let step = extent / Vec2::new((width.max(2) - 1) as f32, (depth.max(2) - 1) as f32);
+
+        let mut slope = Vec2::ZERO;
+        for row in 0..depth {
+            for column in 0..width {
+                let height = heights[row * width + column];
+                if column + 1 < width {
+                    let next = heights[row * width + column + 1];
+                    slope.x = slope.x.max((next - height).abs() / step.x);
+                }
+                if row + 1 < depth {
+                    let next = heights[(row + 1) * width + column];
+                    slope.y = slope.y.max((next - height).abs() / step.y);
+                }
+            }
+        }
+
+        Some(Self {
+            width,
+            depth,
+            max_height: heights.iter().copied().fold(0., f32::max),
+            heights,
+            extent,
+            lipschitz: (1. + slope.length_squared()).sqrt(),
+        })
+    }
+
+    /// Create a height field from the first channel of an image
+    ///
+    /// Returns `None` if the image's format isn't supported
+    pub fn from_image(image: &Image, extent: Vec2, height_scale: f32) -> Option<Self> {
+        let width = image.texture_descriptor.size.width as usize;
+        let depth = image.texture_descriptor.size.height as usize;
+        let heights = match image.texture_descriptor.format {
+            TextureFormat::R8Unorm => read_channel(&image.data, 1, |pixel| pixel[0] as f32 / 255.),
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+                read_channel(&image.data, 4, |pixel| pixel[0] as f32 / 255.)
+            }
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+                read_channel(&image.data, 4, |pixel| pixel[2] as f32 / 255.)
+            }
+            TextureFormat::R16Unorm => read_channel(&image.data, 2, |pixel| {
+                u16::from_le_bytes([pixel[0], pixel[1]]) as f32 / 65535.
+            }),
+            TextureFormat::R32Float => read_channel(&image.data, 4, |pixel| {
+                f32::from_le_bytes([pixel[0], pixel[1], pixel[2], pixel[3]])
+            }),
+            _ => return None,
+        };
+        Self::new(
+            width,
+            depth,
+            &heights[..heights.len().min(width * depth)],
+            extent,
+            height_scale,
+        )
+    }
+
+    /// Get the height of the height field at a point on the XZ plane, clamped to the edges
+    pub fn height_at(&self, position: Vec2) -> f32 {
+        let cells = Vec2::new((self.width - 1) as f32, (self.depth - 1) as f32);
+        let sample = ((position / self.extent + 0.5) * cells).clamp(Vec2::ZERO, cells);
+        let start = sample.floor();
+        let t = sample - start;
+        let column = start.x as usize;
+        let row = start.y as usize;
+        let next_column = (column + 1).min(self.width - 1);
+        let next_row = (row + 1).min(self.depth - 1);
+
+        let height = |column: usize, row: usize| self.heights[row * self.width + column];
+        let near = height(column, row) * (1. - t.x) + height(next_column, row) * t.x;
+        let far = height(column, next_row) * (1. - t.x) + height(next_column, next_row) * t.x;
+        near * (1. - t.y) + far * t.y
+    }
+
+    /// Get the signed distance from the height field at a given point
+    pub fn value_at_point(&self, point: Vec3) -> f32 {
+        let surface = (point.y - self.height_at(Vec2::new(point.x, point.z))) / self.lipschitz;
+        let half_bounds = Vec3::new(self.extent.x, self.max_height, self.extent.y) / 2.;
+        let q = (point - Vec3::Y * half_bounds.y).abs() - half_bounds;
+        let footprint = q.max(Vec3::ZERO).length() + q.max_element().min(0.);
+        surface.max(footprint)
+    }
+
+    /// Get the bounds of the height field
+    pub fn get_bounds(&self) -> (Vec3, Vec3) {
+        let half_extent = self.extent / 2.;
+        (
+            Vec3::new(-half_extent.x, 0., -half_extent.y),
+            Vec3::new(half_extent.x, self.max_height, half_extent.y),
+        )
+    }
+}
+
+fn read_channel(data: &[u8], pixel_size: usize, read: impl Fn(&[u8]) -> f32) -> Vec<f32> {
+    data.chunks_exact(pixel_size).map(read).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    use super::*;
+
+    fn ramp() -> SDFHeightfield {
+        SDFHeightfield::new(2, 2, &[0., 1., 0., 1.], Vec2::new(2., 2.), 2.).unwrap()
+    }
+
+    #[test]
+    fn rejects_mismatched_heights() {
+        assert!(SDFHeightfield::new(2, 2, &[0., 1., 0.], Vec2::ONE, 1.).is_none());
+    }
+
+    #[test]
+    fn calculates_flat_heightfield_sdf() {
+        let sdf = SDFHeightfield::new(2, 2, &[0.5; 4], Vec2::new(4., 4.), 2.).unwrap();
+
+        let interior = sdf.value_at_point(Vec3::Y * 0.5);
+        let surface = sdf.value_at_point(Vec3::new(1., 1., -1.));
+        let outside = sdf.value_at_point(Vec3::new(0., 1.5, 1.));
+        let beside = sdf.value_at_point(Vec3::new(3., 0.5, 0.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+        assert_float_absolute_eq!(beside, 1.);
+    }
+
+    #[test]
+    fn interpolates_heights() {
+        let sdf = ramp();
+
+        assert_float_absolute_eq!(sdf.height_at(Vec2::new(-1., 0.)), 0.);
+        assert_float_absolute_eq!(sdf.height_at(Vec2::new(0., 0.5)), 1.);
+        assert_float_absolute_eq!(sdf.height_at(Vec2::new(0.5, -1.)), 1.5);
+        assert_float_absolute_eq!(sdf.height_at(Vec2::new(5., 0.)), 2.);
+    }
+
+    #[test]
+    fn sloped_heightfield_sdf_never_overestimates() {
+        let sdf = ramp();
+
+        let above = sdf.value_at_point(Vec3::new(0., 2., 0.));
+        let surface = sdf.value_at_point(Vec3::new(0., 1., 0.));
+
+        assert_float_absolute_eq!(above, 1. / 2f32.sqrt());
+        assert_float_absolute_eq!(surface, 0.);
+    }
+
+    #[test]
+    fn reads_heights_from_image() {
+        let image = Image::new(
+            Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            vec![0, 255, 0, 255],
+            TextureFormat::R8Unorm,
+        );
+        let sdf = SDFHeightfield::from_image(&image, Vec2::new(2., 2.), 2.).unwrap();
+
+        assert_eq!(sdf, ramp());
+    }
+
+    #[test]
+    fn calculates_heightfield_bounds() {
+        let sdf = ramp();
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, 0.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+}
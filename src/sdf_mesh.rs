@@ -0,0 +1,449 @@
+//! Signed distance fields built from triangle meshes
+use std::collections::HashMap;
+
+use bevy::{
+    prelude::*,
+    render::mesh::{PrimitiveTopology, VertexAttributeValues},
+};
+
+/// The maximum number of triangles stored in a single BVH leaf
+const MAX_LEAF_TRIANGLES: usize = 4;
+
+/// A triangle mesh prepared for signed distance queries
+///
+/// The sign is determined using angle weighted pseudo-normals, so the mesh is expected
+/// to be closed and consistently wound, with counter-clockwise front faces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SDFMesh {
+    /// The welded vertex positions
+    vertices: Vec<Vec3>,
+    /// The triangles, as indices into the vertices, ordered to match the BVH leaves
+    triangles: Vec<[usize; 3]>,
+    /// The pseudo-normals of each triangle's face, edges and vertices
+    normals: Vec<TriangleNormals>,
+    /// The nodes of the BVH, with the root first
+    nodes: Vec<BVHNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TriangleNormals {
+    face: Vec3,
+    /// The edges are in order - AB, BC, CA
+    edges: [Vec3; 3],
+    vertices: [Vec3; 3],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct BVHNode {
+    min: Vec3,
+    max: Vec3,
+    contents: BVHContents,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BVHContents {
+    Branch(usize, usize),
+    Leaf(usize, usize),
+}
+
+/// The feature of a triangle closest to a point
+enum TriangleFeature {
+    Face,
+    Edge(usize),
+    Vertex(usize),
+}
+
+impl SDFMesh {
+    /// Prepare a mesh for distance queries
+    ///
+    /// Returns `None` if the mesh isn't a triangle list or is missing it's positions
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+            return None;
+        }
+        let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION)? {
+            VertexAttributeValues::Float32x3(positions) => positions,
+            _ => return None,
+        };
+        let indices: Vec<usize> = match mesh.indices() {
+            Some(indices) => indices.iter().collect(),
+            None => (0..positions.len()).collect(),
+        };
+        if indices.iter().any(|index| *index >= positions.len()) {
+            return None;
+        }
+        let triangles = indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                [
+                    Vec3::from(positions[triangle[0]]),
+                    Vec3::from(positions[triangle[1]]),
+                    Vec3::from(positions[triangle[2]]),
+                ]
+            })
+            .collect::<Vec<_>>();
+        Some(Self::from_triangles(&triangles))
+    }
+
+    /// Prepare a list of triangles for distance queries
+    pub fn from_triangles(triangles: &[[Vec3; 3]]) -> Self {
+        let mut vertices = Vec::<Vec3>::new();
+        let mut welded = HashMap::<[u32; 3], usize>::new();
+        // Triangles without any area would have no face normal to determine the sign
+        let mut triangles = triangles
+            .iter()
+            .filter(|[a, b, c]| (*b - *a).cross(*c - *a).normalize_or_zero() != Vec3::ZERO)
+            .map(|triangle| {
+                triangle.map(|vertex| {
+                    // Adding zero turns -0.0 into 0.0, so both weld to the same vertex
+                    let vertex = vertex + Vec3::ZERO;
+                    *welded
+                        .entry([vertex.x.to_bits(), vertex.y.to_bits(), vertex.z.to_bits()])
+                        .or_insert_with(|| {
+                            vertices.push(vertex);
+                            vertices.len() - 1
+                        })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut nodes = Vec::new();
+        if !triangles.is_empty() {
+            let count = triangles.len();
+            build_bvh(&vertices, &mut triangles, 0, count, &mut nodes);
+        }
+
+        let faces = triangles
+            .iter()
+            .map(|[a, b, c]| {
+                (vertices[*b] - vertices[*a])
+                    .cross(vertices[*c] - vertices[*a])
+                    .normalize_or_zero()
+            })
+            .collect::<Vec<_>>();
+
+        let mut edge_normals = HashMap::<(usize, usize), Vec3>::new();
+        let mut vertex_normals = vec![Vec3::ZERO; vertices.len()];
+        for (triangle, face) in triangles.iter().zip(faces.iter()) {
+            for corner in 0..3 {
+                let current = triangle[corner];
+                let next = triangle[(corner + 1) % 3];
+                let previous = triangle[(corner + 2) % 3];
+                *edge_normals
+                    .entry((current.min(next), current.max(next)))
+                    .or_insert(Vec3::ZERO) += *face;
+                let angle = (vertices[next] - vertices[current])
+                    .angle_between(vertices[previous] - vertices[current]);
+                if angle.is_finite() {
+                    vertex_normals[current] += *face * angle;
+                }
+            }
+        }
+
+        let normals = triangles
+            .iter()
+            .zip(faces.iter())
+            .map(|(triangle, face)| TriangleNormals {
+                face: *face,
+                edges: [0, 1, 2].map(|corner| {
+                    let current = triangle[corner];
+                    let next = triangle[(corner + 1) % 3];
+                    edge_normals[&(current.min(next), current.max(next))]
+                }),
+                vertices: triangle.map(|vertex| vertex_normals[vertex]),
+            })
+            .collect();
+
+        Self {
+            vertices,
+            triangles,
+            normals,
+            nodes,
+        }
+    }
+
+    /// Get the signed distance from the mesh at a given point
+    pub fn value_at_point(&self, point: Vec3) -> f32 {
+        let mut closest = f32::INFINITY;
+        let mut sign = 1.;
+        let mut stack = Vec::with_capacity(32);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if aabb_distance_squared(point, node.min, node.max) >= closest {
+                continue;
+            }
+            match node.contents {
+                BVHContents::Branch(left, right) => {
+                    let left_distance =
+                        aabb_distance_squared(point, self.nodes[left].min, self.nodes[left].max);
+                    let right_distance =
+                        aabb_distance_squared(point, self.nodes[right].min, self.nodes[right].max);
+                    if left_distance < right_distance {
+                        stack.push(right);
+                        stack.push(left);
+                    } else {
+                        stack.push(left);
+                        stack.push(right);
+                    }
+                }
+                BVHContents::Leaf(start, end) => {
+                    for triangle in start..end {
+                        let [a, b, c] = self.triangles[triangle].map(|v| self.vertices[v]);
+                        let (closest_point, feature) = closest_point_on_triangle(point, a, b, c);
+                        let offset = point - closest_point;
+                        let distance = offset.length_squared();
+                        if distance < closest {
+                            closest = distance;
+                            let normals = &self.normals[triangle];
+                            let normal = match feature {
+                                TriangleFeature::Face => normals.face,
+                                TriangleFeature::Edge(edge) => normals.edges[edge],
+                                TriangleFeature::Vertex(vertex) => normals.vertices[vertex],
+                            };
+                            sign = if offset.dot(normal) < 0. { -1. } else { 1. };
+                        }
+                    }
+                }
+            }
+        }
+
+        sign * closest.sqrt()
+    }
+
+    /// Get the bounds of the mesh
+    pub fn get_bounds(&self) -> (Vec3, Vec3) {
+        self.nodes
+            .first()
+            .map(|node| (node.min, node.max))
+            .unwrap_or((Vec3::ZERO, Vec3::ZERO))
+    }
+}
+
+/// Recursively build the BVH for the triangles between start and end, returning the node's index
+fn build_bvh(
+    vertices: &[Vec3],
+    triangles: &mut [[usize; 3]],
+    start: usize,
+    end: usize,
+    nodes: &mut Vec<BVHNode>,
+) -> usize {
+    let (min, max) = triangles[start..end].iter().flatten().fold(
+        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+        |(min, max), vertex| (min.min(vertices[*vertex]), max.max(vertices[*vertex])),
+    );
+    let index = nodes.len();
+    nodes.push(BVHNode {
+        min,
+        max,
+        contents: BVHContents::Leaf(start, end),
+    });
+    if end - start <= MAX_LEAF_TRIANGLES {
+        return index;
+    }
+
+    let centroid = |triangle: &[usize; 3]| {
+        (vertices[triangle[0]] + vertices[triangle[1]] + vertices[triangle[2]]) / 3.
+    };
+    let size = max - min;
+    let axis = if size.x >= size.y && size.x >= size.z {
+        0
+    } else if size.y >= size.z {
+        1
+    } else {
+        2
+    };
+    triangles[start..end].sort_by(|a, b| centroid(a)[axis].total_cmp(&centroid(b)[axis]));
+    let middle = (start + end) / 2;
+
+    let left = build_bvh(vertices, triangles, start, middle, nodes);
+    let right = build_bvh(vertices, triangles, middle, end, nodes);
+    nodes[index].contents = BVHContents::Branch(left, right);
+    index
+}
+
+fn aabb_distance_squared(point: Vec3, min: Vec3, max: Vec3) -> f32 {
+    (min - point)
+        .max(point - max)
+        .max(Vec3::ZERO)
+        .length_squared()
+}
+
+/// Find the closest point on a triangle, along with the feature of the triangle it lies on
+fn closest_point_on_triangle(point: Vec3, a: Vec3, b: Vec3, c: Vec3) -> (Vec3, TriangleFeature) {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0. && d2 <= 0. {
+        return (a, TriangleFeature::Vertex(0));
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0. && d4 <= d3 {
+        return (b, TriangleFeature::Vertex(1));
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0. && d1 >= 0. && d3 <= 0. {
+        let v = d1 / (d1 - d3);
+        return (a + ab * v, TriangleFeature::Edge(0));
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0. && d5 <= d6 {
+        return (c, TriangleFeature::Vertex(2));
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0. && d2 >= 0. && d6 <= 0. {
+        let w = d2 / (d2 - d6);
+        return (a + ac * w, TriangleFeature::Edge(2));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0. && (d4 - d3) >= 0. && (d5 - d6) >= 0. {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * w, TriangleFeature::Edge(1));
+    }
+
+    let denominator = 1. / (va + vb + vc);
+    let v = vb * denominator;
+    let w = vc * denominator;
+    (a + ab * v + ac * w, TriangleFeature::Face)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use super::*;
+    use crate::utils::sample_points;
+
+    #[test]
+    fn rejects_non_triangle_meshes() {
+        let mesh = Mesh::new(PrimitiveTopology::LineList);
+        assert!(SDFMesh::from_mesh(&mesh).is_none());
+    }
+
+    #[test]
+    fn welds_vertices_with_negative_zeros() {
+        let sdf = SDFMesh::from_triangles(&[
+            [Vec3::ZERO, Vec3::X, Vec3::Y],
+            [
+                Vec3::new(1., -0., 0.),
+                Vec3::new(1., 1., -0.),
+                Vec3::new(-0., 1., -0.),
+            ],
+        ]);
+
+        // The shared edge's pseudo-normal sums both faces
+        let shared_edges = sdf
+            .normals
+            .iter()
+            .flat_map(|normals| normals.edges)
+            .filter(|edge| edge.z > 1.5)
+            .count();
+        assert_eq!(sdf.vertices.len(), 4);
+        assert_eq!(shared_edges, 2);
+    }
+
+    #[test]
+    fn skips_triangles_without_area() {
+        let cube = Mesh::from(shape::Cube { size: 2. });
+        let positions = match cube.attribute(Mesh::ATTRIBUTE_POSITION).unwrap() {
+            VertexAttributeValues::Float32x3(positions) => positions,
+            _ => unreachable!(),
+        };
+        let indices = cube.indices().unwrap().iter().collect::<Vec<_>>();
+        let mut triangles = indices
+            .chunks_exact(3)
+            .map(|triangle| [0, 1, 2].map(|corner| Vec3::from(positions[triangle[corner]])))
+            .collect::<Vec<_>>();
+        triangles.push([
+            Vec3::new(-0.5, 0., 0.95),
+            Vec3::new(0., 0., 0.95),
+            Vec3::new(0.5, 0., 0.95),
+        ]);
+        let sdf = SDFMesh::from_triangles(&triangles);
+
+        assert_eq!(sdf.triangles.len(), 12);
+        assert_float_absolute_eq!(sdf.value_at_point(Vec3::new(0., 0., 0.9)), -0.1);
+    }
+
+    #[test]
+    fn calculates_cube_mesh_sdf() {
+        let sdf = SDFMesh::from_mesh(&Mesh::from(shape::Cube { size: 2. })).unwrap();
+
+        let interior = sdf.value_at_point(Vec3::ZERO);
+        let surface = sdf.value_at_point(Vec3::Y);
+        let outside = sdf.value_at_point(Vec3::new(1.5, 0., 0.));
+        let corner = sdf.value_at_point(Vec3::new(2., 2., 1.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+        assert_float_absolute_eq!(corner, 2f32.sqrt());
+    }
+
+    #[test]
+    fn cube_mesh_matches_box_everywhere() {
+        let sdf = SDFMesh::from_mesh(&Mesh::from(shape::Cube { size: 2. })).unwrap();
+
+        for point in sample_points(7, Vec3::splat(0.55), Vec3::splat(-1.65)) {
+            let q = point.abs() - Vec3::ONE;
+            let expected = q.max(Vec3::ZERO).length() + q.max_element().min(0.);
+            assert_float_absolute_eq!(sdf.value_at_point(point), expected, 1e-5);
+        }
+    }
+
+    #[test]
+    fn sphere_mesh_approximates_sphere() {
+        let sdf = SDFMesh::from_mesh(&Mesh::from(shape::Icosphere {
+            radius: 1.,
+            subdivisions: 4,
+        }))
+        .unwrap();
+
+        for point in sample_points(7, Vec3::splat(0.55), Vec3::splat(-1.65)) {
+            assert_float_absolute_eq!(sdf.value_at_point(point), point.length() - 1., 0.02);
+        }
+    }
+
+    #[test]
+    fn bvh_matches_brute_force() {
+        let sdf = SDFMesh::from_mesh(&Mesh::from(shape::Torus::default())).unwrap();
+
+        for point in sample_points(7, Vec3::splat(0.55), Vec3::splat(-1.65)) {
+            let brute_force = sdf
+                .triangles
+                .iter()
+                .map(|triangle| {
+                    let [a, b, c] = triangle.map(|v| sdf.vertices[v]);
+                    closest_point_on_triangle(point, a, b, c).0.distance(point)
+                })
+                .fold(f32::INFINITY, f32::min);
+            assert_float_absolute_eq!(sdf.value_at_point(point).abs(), brute_force, 1e-5);
+        }
+    }
+
+    #[test]
+    fn calculates_mesh_bounds() {
+        let sdf = SDFMesh::from_mesh(&Mesh::from(shape::Box::new(1., 2., 3.))).unwrap();
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -0.5);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -1.5);
+        assert_float_absolute_eq!(bounds.1.x, 0.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_float_absolute_eq!(bounds.1.z, 1.5);
+    }
+}
@@ -0,0 +1,521 @@
+//! Describes the available SDF modifiers, altering the shape of a single element
+use std::f32::consts::PI;
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+
+use crate::sdf_noise::SDFNoise;
+
+/// The modifiers applied to a single element, in order
+///
+/// Domain modifiers change the point the element is evaluated at, before it's transform is applied -
+/// so the copies are laid out around the origin, and the transform places the original copy.
+/// Deformations are the exception - they are applied in the element's local space, after it's
+/// transform, so they follow the element as it is moved, rotated and scaled.
+/// Distance modifiers change the resulting distance after the element is scaled,
+/// so they are measured in world units - except for displacement, which scales with the element.
+///
+/// Deformations also divide the distance by a correction factor, bounding how much they stretch
+/// space between the point and the element, so the distance never overestimates.
+///
+/// Repetitions are only exact if the primitive fits within a single cell,
+/// otherwise the distance to neighbouring copies can be overestimated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SDFModifier {
+    /// A domain modifier repeating the primitive infinitely, provided the spacing along each axis
+    ///
+    /// Axes with a spacing of 0 aren't repeated, and the bounds are infinite along the repeated axes
+    RepeatInfinite(Vec3),
+    /// A domain modifier repeating the primitive in a grid, centered on the origin
+    RepeatLimited {
+        /// The spacing along each axis
+        spacing: Vec3,
+        /// The number of copies along each axis
+        count: UVec3,
+    },
+    /// A domain modifier repeating the primitive around the Y axis, provided the number of copies
+    ///
+    /// The first copy is the one along the X axis
+    RepeatPolar(u32),
+    /// A domain modifier mirroring the element across the chosen axes, through the origin
+    ///
+    /// The distance is evaluated on the absolute value of the chosen coordinates,
+    /// so only the half of the element on the positive side of each axis is kept
+    Mirror(BVec3),
+    /// A deformation twisting the element around it's local Y axis,
+    /// provided the angle per unit of height
+    Twist(f32),
+    /// A deformation bending the element in it's local XY plane,
+    /// provided the angle per unit along X
+    Bend(f32),
+    /// A deformation tapering the element along it's local Y axis,
+    /// provided the change in size per unit of height
+    ///
+    /// The element keeps it's size at it's local origin, and vanishes where the size reaches 0
+    Taper(f32),
+    /// A distance modifier turning the surface into a shell, provided half of it's thickness
+    ///
+    /// The shell is centered on the original surface, and applying it again creates nested layers
+    Onion(f32),
+    /// A distance modifier hollowing out the inside, provided the thickness of the remaining wall
+    ///
+    /// Unlike `Onion`, the outer surface stays in place
+    Hollow(f32),
+    /// A distance modifier displacing the surface by noise, evaluated at the point in the element's
+    /// local space
+    ///
+    /// The noise moves and scales with the element, and every repeated copy gets the same noise.
+    /// Near the surface, the displaced distance is divided by the noise's Lipschitz correction,
+    /// so steep noise slows down convergence rather than overestimating.
+    /// Further away, the distance is only reduced by the noise's largest amplitude
+    Displace(SDFNoise),
+}
+
+impl SDFModifier {
+    /// Map a point before the element's transform, for domain modifiers
+    ///
+    /// Deformations map a point in the element's local space instead
+    pub fn map_point(&self, point: Vec3) -> Vec3 {
+        match self {
+            SDFModifier::RepeatInfinite(spacing) => {
+                let repeated = point - *spacing * (point / *spacing).round();
+                Vec3::select(spacing.cmpgt(Vec3::ZERO), repeated, point)
+            }
+            SDFModifier::RepeatLimited { spacing, count } => {
+                let last = count.max(UVec3::ONE) - 1;
+                let offset = last.as_vec3() / 2.;
+                let index = ((point / *spacing) + offset)
+                    .round()
+                    .clamp(Vec3::ZERO, last.as_vec3())
+                    - offset;
+                Vec3::select(spacing.cmpgt(Vec3::ZERO), point - *spacing * index, point)
+            }
+            SDFModifier::RepeatPolar(count) => {
+                let angle = 2. * PI / (*count).max(1) as f32;
+                let polar = (point.z.atan2(point.x) + angle / 2.).rem_euclid(angle) - angle / 2.;
+                let radius = point.xz().length();
+                Vec3::new(polar.cos() * radius, point.y, polar.sin() * radius)
+            }
+            SDFModifier::Mirror(axes) => Vec3::select(*axes, point.abs(), point),
+            SDFModifier::Twist(strength) => {
+                let xz = Vec2::from_angle(strength * point.y).rotate(point.xz());
+                Vec3::new(xz.x, point.y, xz.y)
+            }
+            SDFModifier::Bend(strength) => {
+                let xy = Vec2::from_angle(strength * point.x).rotate(point.xy());
+                Vec3::new(xy.x, xy.y, point.z)
+            }
+            SDFModifier::Taper(strength) => {
+                let scale = taper_scale(*strength, point.y).max(f32::EPSILON);
+                Vec3::new(point.x / scale, point.y, point.z / scale)
+            }
+            _ => point,
+        }
+    }
+
+    /// Map the transformed bounds of the element, for domain modifiers
+    ///
+    /// Deformations map the bounds in the element's local space instead
+    pub fn map_bounds(&self, bounds: &(Vec3, Vec3)) -> (Vec3, Vec3) {
+        match self {
+            SDFModifier::RepeatInfinite(spacing) => {
+                let repeated = spacing.cmpgt(Vec3::ZERO);
+                (
+                    Vec3::select(repeated, Vec3::splat(f32::NEG_INFINITY), bounds.0),
+                    Vec3::select(repeated, Vec3::splat(f32::INFINITY), bounds.1),
+                )
+            }
+            SDFModifier::RepeatLimited { spacing, count } => {
+                let offset = *spacing * (count.max(UVec3::ONE) - 1).as_vec3() / 2.;
+                let offset = offset.max(Vec3::ZERO);
+                (bounds.0 - offset, bounds.1 + offset)
+            }
+            SDFModifier::RepeatPolar(_) | SDFModifier::Twist(_) => {
+                let radius = xz_radius(bounds);
+                (
+                    Vec3::new(-radius, bounds.0.y, -radius),
+                    Vec3::new(radius, bounds.1.y, radius),
+                )
+            }
+            SDFModifier::Bend(_) => {
+                let radius = bounds.0.xy().abs().max(bounds.1.xy().abs()).length();
+                (
+                    Vec3::new(-radius, -radius, bounds.0.z),
+                    Vec3::new(radius, radius, bounds.1.z),
+                )
+            }
+            SDFModifier::Taper(strength) => {
+                // The element vanishes past the height where it's size reaches 0
+                let mut bounds = *bounds;
+                if *strength < 0. {
+                    bounds.1.y = bounds.1.y.min(-1. / strength).max(bounds.0.y);
+                } else if *strength > 0. {
+                    bounds.0.y = bounds.0.y.max(-1. / strength).min(bounds.1.y);
+                }
+                let scales = [bounds.0.y, bounds.1.y].map(|y| taper_scale(*strength, y).max(0.));
+                if !scales.iter().all(|scale| scale.is_finite()) {
+                    return (
+                        Vec3::new(f32::NEG_INFINITY, bounds.0.y, f32::NEG_INFINITY),
+                        Vec3::new(f32::INFINITY, bounds.1.y, f32::INFINITY),
+                    );
+                }
+                let min = (bounds.0.xz() * scales[0]).min(bounds.0.xz() * scales[1]);
+                let max = (bounds.1.xz() * scales[0]).max(bounds.1.xz() * scales[1]);
+                (
+                    Vec3::new(min.x, bounds.0.y, min.y),
+                    Vec3::new(max.x, bounds.1.y, max.y),
+                )
+            }
+            SDFModifier::Mirror(axes) => (
+                Vec3::select(*axes, bounds.0.min(-bounds.1), bounds.0),
+                Vec3::select(*axes, bounds.1.max(-bounds.0), bounds.1),
+            ),
+            _ => *bounds,
+        }
+    }
+
+    /// Map the transformed bounds of the element when baking, for domain modifiers
+    ///
+    /// Infinite repetitions are clipped to the copy at the origin
+    pub fn map_bake_bounds(&self, bounds: &(Vec3, Vec3)) -> (Vec3, Vec3) {
+        match self {
+            SDFModifier::RepeatInfinite(_) => *bounds,
+            _ => self.map_bounds(bounds),
+        }
+    }
+
+    /// Whether the modifier deforms space, so the distance needs to be corrected
+    pub fn is_deformation(&self) -> bool {
+        matches!(
+            self,
+            SDFModifier::Twist(_) | SDFModifier::Bend(_) | SDFModifier::Taper(_)
+        )
+    }
+
+    /// Correct the distance of a deformed element, provided a point within it's deformed bounds
+    ///
+    /// The distance is divided by how much the deformation stretches space within the bounds,
+    /// around the point and no further than the distance itself reaches - so the correction stays
+    /// finite, even for unbounded elements, and the distance never overestimates.
+    pub fn correct_distance(&self, point: Vec3, distance: f32, bounds: &(Vec3, Vec3)) -> f32 {
+        if !distance.is_finite() {
+            return distance;
+        }
+        let mut reach = distance.abs();
+        if let SDFModifier::Taper(strength) = self {
+            // Only reach as far as the element keeps at least half of it's size at the point
+            if *strength != 0. {
+                let scale = taper_scale(*strength, point.y).max(0.);
+                reach = reach.min(scale / (2. * strength.abs()));
+            }
+        }
+        if reach <= 0. {
+            return 0.;
+        }
+        let region = (bounds.0.max(point - reach), bounds.1.min(point + reach));
+
+        let lipschitz = match self {
+            SDFModifier::Twist(strength) => 1. + strength.abs() * xz_radius(&region),
+            SDFModifier::Bend(strength) => {
+                let radius = region.0.xy().abs().max(region.1.xy().abs()).length();
+                1. + strength.abs() * radius
+            }
+            SDFModifier::Taper(strength) => {
+                let min =
+                    taper_scale(*strength, region.0.y).min(taper_scale(*strength, region.1.y));
+                (1. / min).max(1.) + strength.abs() * xz_radius(&region) / (min * min)
+            }
+            _ => 1.,
+        };
+        (distance.abs() / lipschitz).min(reach).copysign(distance)
+    }
+
+    /// Process the distance from an element, for distance modifiers
+    ///
+    /// The point is in the element's local space, and the scale is the element's scale
+    pub fn value(&self, point: Vec3, scale: f32, distance: f32) -> f32 {
+        match self {
+            SDFModifier::Onion(thickness) => distance.abs() - thickness,
+            SDFModifier::Hollow(thickness) => distance.max(-distance - thickness),
+            SDFModifier::Displace(noise) => {
+                // The displaced surface stays within the noise's amplitude of the original surface
+                // Scaling the noise with the element leaves it's Lipschitz bound unchanged
+                let amplitude = noise.max_amplitude() * scale;
+                if distance.abs() > amplitude {
+                    distance - amplitude.copysign(distance)
+                } else {
+                    (distance + noise.value_at_point(point) * scale) / (1. + noise.lipschitz())
+                }
+            }
+            _ => distance,
+        }
+    }
+
+    /// Process the bounds of an element, for distance modifiers, provided the element's scale
+    pub fn get_bounds(&self, bounds: &(Vec3, Vec3), scale: f32) -> (Vec3, Vec3) {
+        match self {
+            SDFModifier::Onion(thickness) => {
+                let thickness = thickness.max(0.);
+                (bounds.0 - thickness, bounds.1 + thickness)
+            }
+            SDFModifier::Displace(noise) => {
+                let amplitude = noise.max_amplitude() * scale;
+                (bounds.0 - amplitude, bounds.1 + amplitude)
+            }
+            _ => *bounds,
+        }
+    }
+}
+
+/// The distance of the furthest corner of the bounds from the Y axis
+fn xz_radius(bounds: &(Vec3, Vec3)) -> f32 {
+    bounds.0.xz().abs().max(bounds.1.xz().abs()).length()
+}
+
+/// The size of a tapered element at a given height
+fn taper_scale(strength: f32, height: f32) -> f32 {
+    if strength == 0. {
+        1.
+    } else {
+        1. + strength * height
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_float_eq::*;
+
+    use super::*;
+
+    #[test]
+    pub fn onion_creates_shell_around_surface() {
+        let modifier = SDFModifier::Onion(0.25);
+
+        assert_float_absolute_eq!(modifier.value(Vec3::ZERO, 1., 0.), -0.25);
+        assert_float_absolute_eq!(modifier.value(Vec3::ZERO, 1., -1.), 0.75);
+        assert_float_absolute_eq!(modifier.value(Vec3::ZERO, 1., 1.), 0.75);
+    }
+
+    #[test]
+    pub fn repeated_onion_creates_nested_shells() {
+        let value = [SDFModifier::Onion(0.5), SDFModifier::Onion(0.1)]
+            .iter()
+            .fold(-1., |distance, modifier| {
+                modifier.value(Vec3::ZERO, 1., distance)
+            });
+
+        assert_float_absolute_eq!(value, 0.4);
+    }
+
+    #[test]
+    pub fn hollow_keeps_outer_surface() {
+        let modifier = SDFModifier::Hollow(0.25);
+
+        assert_float_absolute_eq!(modifier.value(Vec3::ZERO, 1., 0.5), 0.5);
+        assert_float_absolute_eq!(modifier.value(Vec3::ZERO, 1., -0.125), -0.125);
+        assert_float_absolute_eq!(modifier.value(Vec3::ZERO, 1., -1.), 0.75);
+    }
+
+    #[test]
+    pub fn onion_bounds_grow_by_thickness() {
+        let bounds =
+            SDFModifier::Onion(0.5).get_bounds(&(Vec3::new(-1., -2., -0.5), Vec3::ONE), 1.);
+
+        assert_float_absolute_eq!(bounds.0.x, -1.5);
+        assert_float_absolute_eq!(bounds.0.y, -2.5);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.5);
+        assert_float_absolute_eq!(bounds.1.z, 1.5);
+    }
+
+    #[test]
+    pub fn hollow_bounds_are_unchanged() {
+        let bounds = (Vec3::new(-1., -2., -0.5), Vec3::ONE);
+
+        assert_eq!(SDFModifier::Hollow(0.5).get_bounds(&bounds, 1.), bounds);
+    }
+
+    #[test]
+    pub fn repeats_infinitely() {
+        let modifier = SDFModifier::RepeatInfinite(Vec3::new(2., 0., 3.));
+        let point = modifier.map_point(Vec3::new(5.5, 7., -4.));
+
+        assert_float_absolute_eq!(point.x, -0.5);
+        assert_float_absolute_eq!(point.y, 7.);
+        assert_float_absolute_eq!(point.z, -1.);
+    }
+
+    #[test]
+    pub fn repeats_within_limited_grid() {
+        let modifier = SDFModifier::RepeatLimited {
+            spacing: Vec3::splat(2.),
+            count: UVec3::new(3, 2, 1),
+        };
+        let inside = modifier.map_point(Vec3::new(2.5, 1.5, 0.5));
+        let outside = modifier.map_point(Vec3::new(-5., -4., 3.));
+
+        assert_float_absolute_eq!(inside.x, 0.5);
+        assert_float_absolute_eq!(inside.y, 0.5);
+        assert_float_absolute_eq!(inside.z, 0.5);
+        assert_float_absolute_eq!(outside.x, -3.);
+        assert_float_absolute_eq!(outside.y, -3.);
+        assert_float_absolute_eq!(outside.z, 3.);
+    }
+
+    #[test]
+    pub fn repeats_around_the_y_axis() {
+        let modifier = SDFModifier::RepeatPolar(4);
+        let point = modifier.map_point(Vec3::new(0.1, 1., -2.));
+
+        assert_float_absolute_eq!(point.x, 2.);
+        assert_float_absolute_eq!(point.y, 1.);
+        assert_float_absolute_eq!(point.z, 0.1);
+    }
+
+    #[test]
+    pub fn infinite_repetition_bounds_are_infinite() {
+        let bounds = (Vec3::NEG_ONE, Vec3::ONE);
+        let modifier = SDFModifier::RepeatInfinite(Vec3::new(2., 0., 0.));
+        let repeated = modifier.map_bounds(&bounds);
+
+        assert_eq!(repeated.0.x, f32::NEG_INFINITY);
+        assert_eq!(repeated.1.x, f32::INFINITY);
+        assert_float_absolute_eq!(repeated.0.y, -1.);
+        assert_float_absolute_eq!(repeated.1.z, 1.);
+        assert_eq!(modifier.map_bake_bounds(&bounds), bounds);
+    }
+
+    #[test]
+    pub fn limited_repetition_bounds_cover_grid() {
+        let bounds = SDFModifier::RepeatLimited {
+            spacing: Vec3::splat(2.),
+            count: UVec3::new(3, 2, 1),
+        }
+        .map_bounds(&(Vec3::splat(-0.5), Vec3::splat(0.5)));
+
+        assert_float_absolute_eq!(bounds.0.x, -2.5);
+        assert_float_absolute_eq!(bounds.0.y, -1.5);
+        assert_float_absolute_eq!(bounds.0.z, -0.5);
+        assert_float_absolute_eq!(bounds.1.x, 2.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.5);
+        assert_float_absolute_eq!(bounds.1.z, 0.5);
+    }
+
+    #[test]
+    pub fn polar_repetition_bounds_cover_circle() {
+        let bounds = SDFModifier::RepeatPolar(6)
+            .map_bounds(&(Vec3::new(2., -1., -1.), Vec3::new(4., 1., 3.)));
+
+        assert_float_absolute_eq!(bounds.0.x, -5.);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -5.);
+        assert_float_absolute_eq!(bounds.1.x, 5.);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_float_absolute_eq!(bounds.1.z, 5.);
+    }
+
+    #[test]
+    pub fn mirrors_chosen_axes() {
+        let point =
+            SDFModifier::Mirror(BVec3::new(true, false, true)).map_point(Vec3::new(-1., -2., 3.));
+
+        assert_eq!(point, Vec3::new(1., -2., 3.));
+    }
+
+    #[test]
+    pub fn mirror_bounds_are_reflected() {
+        let bounds = SDFModifier::Mirror(BVec3::new(true, true, false))
+            .map_bounds(&(Vec3::new(1., -3., -1.), Vec3::new(2., 1., 4.)));
+
+        assert_eq!(bounds.0, Vec3::new(-2., -3., -1.));
+        assert_eq!(bounds.1, Vec3::new(2., 3., 4.));
+    }
+
+    #[test]
+    pub fn twists_around_the_y_axis() {
+        let point = SDFModifier::Twist(PI / 4.).map_point(Vec3::new(1., 2., 0.));
+
+        assert_float_absolute_eq!(point.x, 0.);
+        assert_float_absolute_eq!(point.y, 2.);
+        assert_float_absolute_eq!(point.z, 1.);
+    }
+
+    #[test]
+    pub fn tapers_along_the_y_axis() {
+        let point = SDFModifier::Taper(-0.25).map_point(Vec3::new(1., 2., -0.5));
+
+        assert_float_absolute_eq!(point.x, 2.);
+        assert_float_absolute_eq!(point.y, 2.);
+        assert_float_absolute_eq!(point.z, -1.);
+    }
+
+    #[test]
+    pub fn taper_bounds_cover_both_ends() {
+        let bounds = SDFModifier::Taper(0.5).map_bounds(&(Vec3::new(-1., -1., 0.), Vec3::ONE * 2.));
+
+        assert_float_absolute_eq!(bounds.0.x, -2.);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, 0.);
+        assert_float_absolute_eq!(bounds.1.x, 4.);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 4.);
+    }
+
+    #[test]
+    pub fn deformation_corrections() {
+        let bounds = (Vec3::new(-3., -1., -4.), Vec3::new(3., 1., 4.));
+        let point = Vec3::new(3., 0., 4.);
+
+        let twist = SDFModifier::Twist(0.5).correct_distance(point, 7., &bounds);
+        let bend = SDFModifier::Bend(-0.5).correct_distance(point, -5., &bounds);
+        let taper = SDFModifier::Taper(0.5).correct_distance(point, 6., &bounds);
+        let onion = SDFModifier::Onion(0.5).correct_distance(point, 2., &bounds);
+
+        assert_float_absolute_eq!(twist, 7. / 3.5);
+        assert_float_absolute_eq!(bend, -5. / (1. + 0.5 * 10f32.sqrt()));
+        assert_float_absolute_eq!(taper, 0.5);
+        assert_float_absolute_eq!(onion, 2.);
+        assert!(!SDFModifier::Onion(0.5).is_deformation());
+    }
+
+    #[test]
+    pub fn corrections_stay_finite_when_unbounded() {
+        let bounds = (Vec3::splat(f32::NEG_INFINITY), Vec3::splat(f32::INFINITY));
+        let point = Vec3::new(3., 0., 4.);
+
+        let twist = SDFModifier::Twist(0.5).correct_distance(point, 1., &bounds);
+        let taper = SDFModifier::Taper(-0.5).correct_distance(point, 1., &bounds);
+
+        assert_float_absolute_eq!(twist, 1. / (1. + 0.5 * Vec2::new(4., 5.).length()));
+        assert!(taper > 0. && taper <= 1.);
+    }
+
+    #[test]
+    pub fn taper_bounds_stop_where_element_vanishes() {
+        let bounds =
+            SDFModifier::Taper(-0.5).map_bounds(&(Vec3::new(-1., -1., -1.), Vec3::ONE * 3.));
+
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.x, 4.5);
+    }
+
+    #[test]
+    pub fn displaces_distance_by_noise() {
+        let noise = SDFNoise::new().with_seed(3).with_amplitude(0.2);
+        let modifier = SDFModifier::Displace(noise.clone());
+        let point = Vec3::new(0.4, 1.2, -0.7);
+
+        assert_float_absolute_eq!(
+            modifier.value(point, 1., 0.25),
+            (0.25 + noise.value_at_point(point)) / (1. + noise.lipschitz())
+        );
+
+        assert_float_absolute_eq!(modifier.value(point, 1., 2.), 2. - 0.375);
+        assert_float_absolute_eq!(modifier.value(point, 1., -1.), -1. + 0.375);
+
+        let bounds = modifier.get_bounds(&(Vec3::NEG_ONE, Vec3::ONE), 1.);
+        assert_float_absolute_eq!(bounds.0.x, -1.375);
+        assert_float_absolute_eq!(bounds.1.z, 1.375);
+    }
+}
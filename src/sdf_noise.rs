@@ -0,0 +1,201 @@
+//! Deterministic procedural noise, used to displace the surface of elements
+use bevy::prelude::*;
+
+/// The largest gradient of a single octave of value noise
+///
+/// The quintic fade has a slope of at most 15/8, across a difference of at most 2 along each axis
+const VALUE_NOISE_GRADIENT: f32 = 3.75 * 1.732_050_8;
+
+/// Seeded fractal value noise, summing octaves of increasing frequency and decreasing amplitude
+///
+/// It only uses integer hashing and basic floating point arithmetic,
+/// so the same seed gives the same values on every platform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SDFNoise {
+    /// The seed used to generate the noise
+    pub seed: u32,
+    /// The number of octaves summed together
+    pub octaves: u32,
+    /// The frequency of the first octave
+    pub frequency: f32,
+    /// The frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// The amplitude multiplier between octaves
+    pub gain: f32,
+    /// The amplitude of the first octave
+    pub amplitude: f32,
+}
+
+impl Default for SDFNoise {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            octaves: 4,
+            frequency: 1.,
+            lacunarity: 2.,
+            gain: 0.5,
+            amplitude: 0.1,
+        }
+    }
+}
+
+impl SDFNoise {
+    /// Make default `SDFNoise`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `SDFNoise` with a seed
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Make `SDFNoise` with a number of octaves
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    /// Make `SDFNoise` with the frequency of the first octave
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Make `SDFNoise` with the frequency multiplier between octaves
+    pub fn with_lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    /// Make `SDFNoise` with the amplitude multiplier between octaves
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Make `SDFNoise` with the amplitude of the first octave
+    pub fn with_amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    /// Get the value of the noise at a given point
+    pub fn value_at_point(&self, point: Vec3) -> f32 {
+        let mut frequency = self.frequency;
+        let mut amplitude = self.amplitude;
+        let mut value = 0.;
+        for octave in 0..self.octaves {
+            value += amplitude * value_noise(self.seed.wrapping_add(octave), point * frequency);
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        value
+    }
+
+    /// Get the largest absolute value the noise can reach
+    pub fn max_amplitude(&self) -> f32 {
+        (0..self.octaves)
+            .map(|octave| (self.amplitude * self.gain.powi(octave as i32)).abs())
+            .sum()
+    }
+
+    /// Get the largest rate of change of the noise, used to correct displaced distances
+    pub fn lipschitz(&self) -> f32 {
+        (0..self.octaves)
+            .map(|octave| {
+                let amplitude = self.amplitude * self.gain.powi(octave as i32);
+                let frequency = self.frequency * self.lacunarity.powi(octave as i32);
+                (amplitude * frequency).abs() * VALUE_NOISE_GRADIENT
+            })
+            .sum()
+    }
+}
+
+/// A single octave of value noise, between -1 and 1
+fn value_noise(seed: u32, point: Vec3) -> f32 {
+    let cell = point.floor();
+    let offset = point - cell;
+    let fade = offset * offset * offset * (offset * (offset * 6. - 15.) + 10.);
+    let cell = cell.as_ivec3();
+
+    let corner = |x: i32, y: i32, z: i32| hash(seed, cell + IVec3::new(x, y, z));
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), fade.x);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), fade.x);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), fade.x);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), fade.x);
+    lerp(lerp(x00, x10, fade.y), lerp(x01, x11, fade.y), fade.z)
+}
+
+/// Hash a lattice point into a value between -1 and 1
+fn hash(seed: u32, cell: IVec3) -> f32 {
+    let mut hash = seed.wrapping_mul(0x9e37_79b9)
+        ^ (cell.x as u32).wrapping_mul(0x85eb_ca6b)
+        ^ (cell.y as u32).wrapping_mul(0xc2b2_ae35)
+        ^ (cell.z as u32).wrapping_mul(0x27d4_eb2f);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2c1b_3c6d);
+    hash ^= hash >> 12;
+    hash = hash.wrapping_mul(0x297a_2d39);
+    hash ^= hash >> 15;
+    (hash >> 8) as f32 / 8_388_607.5 - 1.
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use super::*;
+    use crate::utils::sample_points;
+
+    #[test]
+    fn noise_is_deterministic() {
+        let noise = SDFNoise::new().with_seed(42);
+        let point = Vec3::new(0.3, -1.7, 2.2);
+
+        assert_eq!(noise.value_at_point(point), 0.022_235_844);
+        assert_eq!(
+            noise.value_at_point(point),
+            noise.clone().value_at_point(point)
+        );
+        assert_ne!(
+            noise.value_at_point(point),
+            noise.with_seed(7).value_at_point(point)
+        );
+    }
+
+    #[test]
+    fn noise_without_octaves_is_zero() {
+        let noise = SDFNoise::new().with_octaves(0);
+
+        assert_eq!(noise.value_at_point(Vec3::new(0.5, 0.2, -3.)), 0.);
+        assert_eq!(noise.max_amplitude(), 0.);
+        assert_eq!(noise.lipschitz(), 0.);
+    }
+
+    #[test]
+    fn noise_stays_within_amplitude() {
+        let noise = SDFNoise::new().with_amplitude(0.5).with_gain(0.6);
+
+        assert_float_absolute_eq!(noise.max_amplitude(), 0.5 * (1. + 0.6 + 0.36 + 0.216));
+        for point in sample_points(8, Vec3::new(1., 1.3, 0.7) * 0.37, Vec3::splat(-1.)) {
+            assert!(noise.value_at_point(point).abs() <= noise.max_amplitude());
+        }
+    }
+
+    #[test]
+    fn noise_is_lipschitz_bound() {
+        let noise = SDFNoise::new().with_frequency(1.5).with_octaves(3);
+        let lipschitz = noise.lipschitz();
+
+        let points = || sample_points(8, Vec3::new(1., 1.3, 0.7) * 0.37, Vec3::splat(-1.));
+        for a in points() {
+            for b in points().step_by(11) {
+                let difference = (noise.value_at_point(a) - noise.value_at_point(b)).abs();
+                assert!(difference <= lipschitz * a.distance(b) + 1e-5);
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! The root SDF object
 use crate::{
+    sdf_modifiers::SDFModifier,
     sdf_operations::SDFOperators,
     sdf_primitives::SDFPrimitive,
     sdf_shader::{SDFShader, ATTRIBUTE_UV_3D},
@@ -27,6 +28,10 @@ pub struct SDFElement {
     inverse: Mat4,
     // the scale
     scale: f32,
+    /// The radius used to round off the primitive
+    rounding: f32,
+    /// Modifiers applied to the distance, in order
+    pub modifiers: Vec<SDFModifier>,
     /// Operation for joining the object with the previous object
     pub operation: SDFOperators,
 }
@@ -39,6 +44,8 @@ impl Default for SDFElement {
             inverse: transform.inverse(),
             transform,
             scale: 1.,
+            rounding: 0.,
+            modifiers: vec![],
             operation: SDFOperators::Union,
         }
     }
@@ -89,13 +96,66 @@ impl SDFElement {
         self
     }
 
+    /// Make `SDFElement` with rounded edges
+    ///
+    /// The rounding grows the primitive outwards by the radius, in the primitive's local space
+    pub fn with_rounding(mut self, radius: f32) -> Self {
+        self.rounding = radius.max(0.);
+        self
+    }
+
+    /// Make `SDFElement` with a modifier, applied after any previous modifiers
+    pub fn with_modifier(mut self, modifier: SDFModifier) -> Self {
+        self.modifiers.push(modifier);
+        self
+    }
+
     /// Get the value of the SDF at a given point
     pub fn value_at_point(&self, point: &Vec3) -> f32 {
-        let scale = self.scale;
-        let transform = self.inverse;
-        self.primitive
-            .value_at_point(&(transform.transform_point3(*point)))
-            * scale
+        let point = self.local_point(*point);
+        let value = self.deformed_value(0, point) * self.scale;
+        self.modifiers.iter().fold(value, |value, modifier| {
+            modifier.value(point, self.scale, value)
+        })
+    }
+
+    /// Map a point through the domain modifiers and into the element's local space
+    fn local_point(&self, point: Vec3) -> Vec3 {
+        let point = self
+            .modifiers
+            .iter()
+            .filter(|modifier| !modifier.is_deformation())
+            .fold(point, |point, modifier| modifier.map_point(point));
+        self.inverse.transform_point3(point)
+    }
+
+    /// Get the unscaled value at a point in the element's local space, deformed by the deformations
+    /// from `first` onwards
+    ///
+    /// Deformations are evaluated within their deformed bounds, and use the distance to the bounds
+    /// outside of them
+    fn deformed_value(&self, first: usize, point: Vec3) -> f32 {
+        let (index, modifier) = match self
+            .modifiers
+            .iter()
+            .enumerate()
+            .skip(first)
+            .find(|(_, modifier)| modifier.is_deformation())
+        {
+            Some(deformation) => deformation,
+            None => return self.primitive.value_at_point(&point) - self.rounding,
+        };
+
+        let bounds = modifier.map_bounds(&self.deformed_bounds(index + 1));
+        let clamped = point.clamp(bounds.0, bounds.1);
+        let outside = point.distance(clamped);
+        let value = self.deformed_value(index + 1, modifier.map_point(clamped));
+        let value = modifier.correct_distance(clamped, value, &bounds);
+        if outside > 0. {
+            outside.max(value - outside)
+        } else {
+            value
+        }
     }
 
     /// Get the value, taking into account previous values
@@ -106,26 +166,316 @@ impl SDFElement {
 
     /// Get the bounds of the element, potentially given a previous element
     pub fn get_bounds(&self, previous: &Option<(Vec3, Vec3)>) -> (Vec3, Vec3) {
-        let bounds = self.primitive.get_bounds();
-        let bounds = (
-            self.transform.transform_point3(bounds.0),
-            self.transform.transform_point3(bounds.1),
-        );
-        let mut bounds = (bounds.0.min(bounds.1), bounds.0.max(bounds.1));
+        let mut bounds = self.modified_bounds(SDFModifier::map_bounds);
 
         if let Some(previous) = previous {
             bounds = self.operation.get_bounds(previous, &bounds);
         }
         bounds
     }
+
+    /// Get the bounds of the element on it's own when baking
+    ///
+    /// Infinite repetitions are clipped to the copy at the origin
+    pub fn get_bake_bounds(&self) -> (Vec3, Vec3) {
+        self.modified_bounds(SDFModifier::map_bake_bounds)
+    }
+
+    /// Get the bounds of the element, with the distance modifiers applied
+    fn modified_bounds(
+        &self,
+        map_bounds: impl Fn(&SDFModifier, &(Vec3, Vec3)) -> (Vec3, Vec3),
+    ) -> (Vec3, Vec3) {
+        let bounds = self.domain_bounds(map_bounds);
+        self.modifiers.iter().fold(bounds, |bounds, modifier| {
+            modifier.get_bounds(&bounds, self.scale)
+        })
+    }
+
+    /// Get the bounds of the transformed primitive, with the domain modifiers mapped in reverse order
+    fn domain_bounds(
+        &self,
+        map_bounds: impl Fn(&SDFModifier, &(Vec3, Vec3)) -> (Vec3, Vec3),
+    ) -> (Vec3, Vec3) {
+        let bounds = transform_bounds(&self.transform, &self.deformed_bounds(0));
+        self.modifiers
+            .iter()
+            .rev()
+            .filter(|modifier| !modifier.is_deformation())
+            .fold(bounds, |bounds, modifier| map_bounds(modifier, &bounds))
+    }
+
+    /// Get the bounds of the primitive in the element's local space, with the deformations from
+    /// `first` onwards mapped in reverse order
+    fn deformed_bounds(&self, first: usize) -> (Vec3, Vec3) {
+        let bounds = self.primitive.get_bounds();
+        let bounds = (bounds.0 - self.rounding, bounds.1 + self.rounding);
+        self.modifiers[first..]
+            .iter()
+            .rev()
+            .filter(|modifier| modifier.is_deformation())
+            .fold(bounds, |bounds, modifier| modifier.map_bounds(&bounds))
+    }
+}
+
+/// A group of SDF nodes, combined with each other in their own local space
+///
+/// The combined group is then joined with the previous node using it's own operation
+#[derive(Debug, Clone)]
+pub struct SDFGroup {
+    /// The child nodes, joined in order
+    pub children: Vec<SDFNode>,
+    /// The transform matrix
+    transform: Mat4,
+    /// The inverse transform matrix
+    inverse: Mat4,
+    /// The scale
+    scale: f32,
+    /// Operation for joining the group with the previous node
+    pub operation: SDFOperators,
+}
+
+impl Default for SDFGroup {
+    fn default() -> Self {
+        let transform = Mat4::from_scale(Vec3::ONE);
+        Self {
+            children: vec![],
+            inverse: transform.inverse(),
+            transform,
+            scale: 1.,
+            operation: SDFOperators::Union,
+        }
+    }
+}
+
+impl SDFGroup {
+    /// Create a new group - short for the default
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `SDFGroup` with an operation
+    pub fn with_operation(mut self, operation: SDFOperators) -> Self {
+        self.operation = operation;
+        self
+    }
+
+    /// Make `SDFGroup` with a translation
+    pub fn with_translation(mut self, translation: Vec3) -> Self {
+        let (scale, rotation, _) = self.transform.to_scale_rotation_translation();
+        self.transform = Mat4::from_scale_rotation_translation(scale, rotation, translation);
+        self.inverse = self.transform.inverse();
+        self
+    }
+
+    /// Make `SDFGroup` with a rotation
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        let (scale, _, translation) = self.transform.to_scale_rotation_translation();
+        self.transform = Mat4::from_scale_rotation_translation(scale, rotation, translation);
+        self.inverse = self.transform.inverse();
+        self
+    }
+
+    /// Make `SDFGroup` with a scale
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        let (_, rotation, translation) = self.transform.to_scale_rotation_translation();
+        self.scale = scale.abs();
+        self.transform =
+            Mat4::from_scale_rotation_translation(Vec3::splat(self.scale), rotation, translation);
+        self.inverse = self.transform.inverse();
+        self
+    }
+
+    /// Add a child node
+    ///
+    /// Empty groups are left out, as they are skipped when joining nodes
+    pub fn with_node(mut self, node: SDFNode) -> Self {
+        if !node.is_empty() {
+            self.children.push(node);
+        }
+        self
+    }
+
+    /// Add a child element
+    pub fn with_element(self, element: SDFElement) -> Self {
+        self.with_node(SDFNode::Element(element))
+    }
+
+    /// Add a child group
+    pub fn with_group(self, group: SDFGroup) -> Self {
+        self.with_node(SDFNode::Group(group))
+    }
+
+    /// Get the value of the SDF at a given point
+    pub fn value_at_point(&self, point: &Vec3) -> f32 {
+        nodes_value_at_point(&self.children, &self.inverse.transform_point3(*point)) * self.scale
+    }
+
+    /// Get the bounds of the group, potentially given a previous node
+    ///
+    /// An empty group leaves the previous bounds unchanged, as it is skipped when joining nodes
+    pub fn get_bounds(&self, previous: &Option<(Vec3, Vec3)>) -> (Vec3, Vec3) {
+        let bounds = match nodes_bounds(&self.children) {
+            Some(bounds) => transform_bounds(&self.transform, &bounds),
+            None => return previous.unwrap_or((Vec3::ZERO, Vec3::ZERO)),
+        };
+
+        match previous {
+            Some(previous) => self.operation.get_bounds(previous, &bounds),
+            None => bounds,
+        }
+    }
+}
+
+/// A node in the tree of an SDF object
+#[derive(Debug, Clone)]
+pub enum SDFNode {
+    /// A single element
+    Element(SDFElement),
+    /// A group of nodes
+    Group(SDFGroup),
+}
+
+impl From<SDFElement> for SDFNode {
+    fn from(element: SDFElement) -> Self {
+        SDFNode::Element(element)
+    }
+}
+
+impl From<SDFGroup> for SDFNode {
+    fn from(group: SDFGroup) -> Self {
+        SDFNode::Group(group)
+    }
+}
+
+impl SDFNode {
+    /// Get the value of the SDF at a given point
+    pub fn value_at_point(&self, point: &Vec3) -> f32 {
+        match self {
+            SDFNode::Element(element) => element.value_at_point(point),
+            SDFNode::Group(group) => group.value_at_point(point),
+        }
+    }
+
+    /// Get the value, taking into account previous values
+    pub fn process_object_at_point(&self, point: &Vec3, previous: f32) -> f32 {
+        let value = self.value_at_point(point);
+        self.operation().value(&previous, &value)
+    }
+
+    /// Get the bounds of the node, potentially given a previous node
+    pub fn get_bounds(&self, previous: &Option<(Vec3, Vec3)>) -> (Vec3, Vec3) {
+        match self {
+            SDFNode::Element(element) => element.get_bounds(previous),
+            SDFNode::Group(group) => group.get_bounds(previous),
+        }
+    }
+
+    /// Get the bounds used to clip unbounded axes when baking
+    ///
+    /// Groups use the finite bake bounds of their children, and can remain unbounded along
+    /// axes none of them bound
+    pub fn get_bake_bounds(&self) -> (Vec3, Vec3) {
+        match self {
+            SDFNode::Element(element) => element.get_bake_bounds(),
+            SDFNode::Group(group) => {
+                transform_bounds(&group.transform, &nodes_clip_bounds(&group.children))
+            }
+        }
+    }
+
+    /// Whether the node has no elements, and so is skipped when joining nodes
+    ///
+    /// Nested groups are checked until the first child with an element - `with_node` leaves out
+    /// empty groups, so for groups built from it that is always the first child
+    pub fn is_empty(&self) -> bool {
+        match self {
+            SDFNode::Element(_) => false,
+            SDFNode::Group(group) => group.children.iter().all(SDFNode::is_empty),
+        }
+    }
+
+    /// The operation joining the node with the previous node
+    pub fn operation(&self) -> &SDFOperators {
+        match self {
+            SDFNode::Element(element) => &element.operation,
+            SDFNode::Group(group) => &group.operation,
+        }
+    }
+}
+
+/// Join a list of nodes in order, skipping empty nodes, and get the value at a given point
+fn nodes_value_at_point(nodes: &[SDFNode], point: &Vec3) -> f32 {
+    nodes
+        .iter()
+        .filter(|node| !node.is_empty())
+        .fold(f32::INFINITY, |value, node| {
+            node.process_object_at_point(point, value)
+        })
+}
+
+/// Join a list of nodes in order, skipping empty nodes, and get their bounds - if there are any
+fn nodes_bounds(nodes: &[SDFNode]) -> Option<(Vec3, Vec3)> {
+    nodes
+        .iter()
+        .filter(|node| !node.is_empty())
+        .fold(None, |bounds, node| Some(node.get_bounds(&bounds)))
+}
+
+/// Get the hull of the finite components of the nodes' bake bounds
+///
+/// Axes that no node bounds remain infinite
+fn nodes_clip_bounds(nodes: &[SDFNode]) -> (Vec3, Vec3) {
+    nodes.iter().filter(|node| !node.is_empty()).fold(
+        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+        |clip, node| {
+            let bounds = node.get_bake_bounds();
+            (
+                finite_or(clip.0.min(bounds.0), clip.0),
+                finite_or(clip.1.max(bounds.1), clip.1),
+            )
+        },
+    )
+}
+
+/// Get the axis aligned bounds containing the transformed bounds
+///
+/// Infinite bounds are supported, and remain infinite along any axis they contribute to
+fn transform_bounds(transform: &Mat4, bounds: &(Vec3, Vec3)) -> (Vec3, Vec3) {
+    let translation = transform.w_axis.truncate();
+    let mut min = translation;
+    let mut max = translation;
+    for column in 0..3 {
+        let axis = transform.col(column).truncate();
+        for row in 0..3 {
+            let weight = axis[row];
+            if weight.abs() < 1e-6 {
+                continue;
+            }
+            let a = weight * bounds.0[column];
+            let b = weight * bounds.1[column];
+            min[row] += a.min(b);
+            max[row] += a.max(b);
+        }
+    }
+    (min, max)
+}
+
+/// Replace any infinite components of a value with the matching component of the fallback
+fn finite_or(value: Vec3, fallback: Vec3) -> Vec3 {
+    Vec3::select(
+        BVec3::new(value.x.is_finite(), value.y.is_finite(), value.z.is_finite()),
+        value,
+        fallback,
+    )
 }
 
 /// The root SDF object
 #[derive(Debug, Clone, TypeUuid, Default)]
 #[uuid = "3e9f6f3f-730c-46d1-8e12-4715f4c6f861"]
 pub struct SDFObject {
-    /// A list of all the elements in the SDF
-    pub elements: Vec<SDFElement>,
+    /// The nodes of the SDF, joined in order
+    pub nodes: Vec<SDFNode>,
     /// The mesh handle for the current SDF object
     pub mesh_handle: Option<Handle<Mesh>>,
     /// The image handle for the current SDF object
@@ -135,25 +485,68 @@ pub struct SDFObject {
 }
 
 impl SDFObject {
-    /// Add Element
-    pub fn with_element(mut self, element: SDFElement) -> Self {
-        self.elements.push(element);
+    /// Add Node
+    ///
+    /// Empty groups are left out, as they are skipped when joining nodes
+    pub fn with_node(mut self, node: SDFNode) -> Self {
+        if !node.is_empty() {
+            self.nodes.push(node);
+        }
         self
     }
 
+    /// Add Element
+    pub fn with_element(self, element: SDFElement) -> Self {
+        self.with_node(SDFNode::Element(element))
+    }
+
+    /// Add Group
+    pub fn with_group(self, group: SDFGroup) -> Self {
+        self.with_node(SDFNode::Group(group))
+    }
+
+    /// Create an SDF Object morphing from `a` to `b`, as `t` goes from 0 to 1
+    ///
+    /// Each object's nodes are placed in their own group, joined with [`SDFOperators::Morph`],
+    /// and the handles are left empty. If either object has no nodes, the other object is used
+    /// as it is, matching the bounds.
+    pub fn morph(a: &SDFObject, b: &SDFObject, t: f32) -> Self {
+        Self::default()
+            .with_group(SDFGroup {
+                children: a.nodes.clone(),
+                ..default()
+            })
+            .with_group(SDFGroup {
+                children: b.nodes.clone(),
+                operation: SDFOperators::Morph(t),
+                ..default()
+            })
+    }
+
     /// Calculate the value of the SDF Object at a given point
     pub fn value_at_point(&self, point: &Vec3) -> f32 {
-        self.elements.iter().fold(f32::INFINITY, |value, element| {
-            element.process_object_at_point(point, value)
-        })
+        nodes_value_at_point(&self.nodes, point)
     }
 
     /// Calculate SDF Object bounds
+    ///
+    /// Unbounded objects use infinite values along the unbounded axes
     pub fn get_bounds(&self) -> (Vec3, Vec3) {
-        self.elements
-            .iter()
-            .fold(None, |value, element| Some(element.get_bounds(&value)))
-            .unwrap_or((Vec3::ZERO, Vec3::ZERO))
+        nodes_bounds(&self.nodes).unwrap_or((Vec3::ZERO, Vec3::ZERO))
+    }
+
+    /// Calculate the bounds used when baking the SDF Object
+    ///
+    /// Any unbounded axes are clipped to the finite bake bounds of the individual nodes,
+    /// and axes that no node bounds are collapsed onto the origin
+    pub fn get_bake_bounds(&self) -> (Vec3, Vec3) {
+        let bounds = self.get_bounds();
+        if bounds.0.is_finite() && bounds.1.is_finite() {
+            return bounds;
+        }
+        let clip = nodes_clip_bounds(&self.nodes);
+        let clip = (finite_or(clip.0, Vec3::ZERO), finite_or(clip.1, Vec3::ZERO));
+        (finite_or(bounds.0, clip.0), finite_or(bounds.1, clip.1))
     }
 
     /// Get the locations of boxes designed to cover the surface at a given size
@@ -223,7 +616,7 @@ impl SDFObject {
         max_lods: usize,
         min_box_size: f32,
     ) -> Vec<(f32, Vec<Vec<Vec3>>)> {
-        let bounds = self.get_bounds();
+        let bounds = self.get_bake_bounds();
         let mut lods: Vec<(f32, Vec<Vec<Vec3>>)> = Vec::new();
 
         loop {
@@ -478,13 +871,23 @@ impl RenderAsset for SDFObject {
 
 #[cfg(test)]
 mod tests {
-    use std::f32::consts::PI;
+    use std::{f32::consts::PI, sync::Arc};
 
     use assert_float_eq::*;
-    use bevy::prelude::{EulerRot, Vec3};
+    use bevy::{
+        math::Vec3Swizzles,
+        prelude::{EulerRot, Vec3},
+    };
 
     use super::*;
-    use crate::sdf_primitives::SDFPrimitive;
+    use crate::{
+        sdf_2d::SDFPrimitive2D,
+        sdf_heightfield::SDFHeightfield,
+        sdf_mesh::SDFMesh,
+        sdf_noise::SDFNoise,
+        sdf_primitives::{CustomPrimitive, SDFPrimitive},
+        utils::sample_points,
+    };
 
     #[test]
     fn translates_a_sdf() {
@@ -560,12 +963,101 @@ mod tests {
         assert_float_absolute_eq!(outside, 0.5);
     }
 
+    #[test]
+    fn rotates_and_translates_a_capsule() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::VerticalCapsule {
+                half_height: 1.,
+                radius: 0.5,
+            })
+            .with_rotation(Quat::from_euler(EulerRot::XYZ, 0., 0., 90. * PI / 180.))
+            .with_translation(Vec3::Y);
+
+        let interior = sdf.value_at_point(&(Vec3::Y + Vec3::X));
+        let surface = sdf.value_at_point(&(Vec3::Y * 1.5));
+        let outside = sdf.value_at_point(&Vec3::new(-2., 1., 0.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn rounds_a_sdf() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Box(Vec3::ONE))
+            .with_rounding(0.5)
+            .with_scale(2.);
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let surface = sdf.value_at_point(&(Vec3::X * 3.));
+        let corner = sdf.value_at_point(&(Vec3::ONE * 2. + Vec3::X * 0.5));
+
+        assert_float_absolute_eq!(interior, -3.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(corner, -0.5);
+    }
+
+    #[test]
+    fn onions_a_scaled_sdf() {
+        let sdf = SDFElement::default()
+            .with_scale(2.)
+            .with_modifier(SDFModifier::Onion(0.5));
+
+        let center = sdf.value_at_point(&Vec3::ZERO);
+        let surface = sdf.value_at_point(&(Vec3::X * 2.5));
+        let inner_surface = sdf.value_at_point(&(Vec3::Y * 1.5));
+
+        assert_float_absolute_eq!(center, 1.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(inner_surface, 0.);
+    }
+
+    #[test]
+    fn hollows_a_pipe() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::CappedCylinder {
+                half_height: 2.,
+                radius: 1.,
+            })
+            .with_modifier(SDFModifier::Hollow(0.25));
+
+        let center = sdf.value_at_point(&Vec3::ZERO);
+        let wall = sdf.value_at_point(&(Vec3::X * 0.875));
+
+        assert_float_absolute_eq!(center, 0.75);
+        assert_float_absolute_eq!(wall, -0.125);
+    }
+
+    #[test]
+    fn rotates_and_translates_an_extrusion() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Extrusion {
+                shape: SDFPrimitive2D::Polygon(vec![vec![
+                    Vec2::new(-1., -1.),
+                    Vec2::new(1., -1.),
+                    Vec2::new(0., 1.),
+                ]]),
+                half_depth: 0.5,
+            })
+            .with_rotation(Quat::from_euler(EulerRot::XYZ, -90. * PI / 180., 0., 0.))
+            .with_translation(Vec3::X);
+
+        let interior = sdf.value_at_point(&Vec3::X);
+        let floor = sdf.value_at_point(&(Vec3::X - Vec3::Y * 0.5));
+        let outside = sdf.value_at_point(&(Vec3::X + Vec3::Z * 2.));
+
+        assert_float_absolute_eq!(interior, -1. / 5f32.sqrt(), 1e-5);
+        assert_float_absolute_eq!(floor, 0., 1e-5);
+        assert_float_absolute_eq!(outside, 1., 1e-5);
+    }
+
     #[test]
     fn union_of_sdfs() {
         let sdf_a = SDFElement::default().with_translation(Vec3::X);
         let sdf_b = SDFElement::default().with_translation(-1. * Vec3::X);
         let sdf = SDFObject {
-            elements: vec![sdf_a, sdf_b],
+            nodes: vec![sdf_a.into(), sdf_b.into()],
             mesh_handle: None,
             image_handle: None,
             material_handle: None,
@@ -596,7 +1088,7 @@ mod tests {
             ..default()
         };
         let sdf = SDFObject {
-            elements: vec![sdf_a, sdf_b],
+            nodes: vec![sdf_a.into(), sdf_b.into()],
             mesh_handle: None,
             image_handle: None,
             material_handle: None,
@@ -627,7 +1119,7 @@ mod tests {
             ..default()
         };
         let sdf = SDFObject {
-            elements: vec![sdf_a, sdf_b],
+            nodes: vec![sdf_a.into(), sdf_b.into()],
             mesh_handle: None,
             image_handle: None,
             material_handle: None,
@@ -642,6 +1134,72 @@ mod tests {
         assert_float_absolute_eq!(outside, 0.5);
     }
 
+    #[test]
+    fn subtraction_from_mesh() {
+        let mesh = SDFMesh::from_mesh(&Mesh::from(shape::Cube { size: 2. })).unwrap();
+        let sdf = SDFObject::default()
+            .with_element(SDFElement::default().with_primitive(SDFPrimitive::Mesh(Arc::new(mesh))))
+            .with_element(
+                SDFElement::default()
+                    .with_translation(Vec3::Y)
+                    .with_operation(SDFOperators::Subtraction),
+            );
+
+        let removed = sdf.value_at_point(&(Vec3::Y * 0.5));
+        let inner_surface = sdf.value_at_point(&Vec3::ZERO);
+        let interior = sdf.value_at_point(&Vec3::new(0.5, -0.5, 0.));
+
+        assert_float_absolute_eq!(removed, 0.5);
+        assert_float_absolute_eq!(inner_surface, 0.);
+        assert_float_absolute_eq!(interior, -0.5);
+
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+    }
+
+    #[test]
+    fn smooth_union_bridges_nearby_spheres() {
+        let sdf = SDFObject::default()
+            .with_element(SDFElement::default().with_translation(Vec3::X * -1.2))
+            .with_element(
+                SDFElement::default()
+                    .with_translation(Vec3::X * 1.2)
+                    .with_operation(SDFOperators::SmoothUnion(1.)),
+            );
+
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::ZERO), -0.05);
+        assert_float_absolute_eq!(sdf.value_at_point(&(Vec3::X * 2.2)), 0.);
+
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -3.2);
+        assert_float_absolute_eq!(bounds.0.y, -2.);
+        assert_float_absolute_eq!(bounds.1.x, 3.2);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+    }
+
+    #[test]
+    fn smooth_union_as_the_first_node_is_finite() {
+        let sdf = SDFObject::default()
+            .with_element(
+                SDFElement::default()
+                    .with_translation(Vec3::X * -1.2)
+                    .with_operation(SDFOperators::SmoothUnion(0.5)),
+            )
+            .with_group(
+                SDFGroup::new().with_element(
+                    SDFElement::default()
+                        .with_translation(Vec3::X * 1.2)
+                        .with_operation(SDFOperators::SmoothUnion(0.5)),
+                ),
+            );
+
+        let above = sdf.value_at_point(&Vec3::new(0., 3., 0.));
+        let expected = Vec2::new(1.2, 3.).length() - 1.;
+        assert_float_absolute_eq!(above, expected, 1e-5);
+        assert_float_absolute_eq!(sdf.value_at_point(&(Vec3::X * -2.2)), 0.);
+    }
+
     #[test]
     fn translates_bounds() {
         let sdf = SDFElement::default().with_translation(Vec3::X);
@@ -672,6 +1230,73 @@ mod tests {
         assert_float_absolute_eq!(bounds.1.z, 1.);
     }
 
+    #[test]
+    fn rotates_bounds_at_an_angle() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Box(Vec3::ONE))
+            .with_rotation(Quat::from_euler(EulerRot::XYZ, 0., 45. * PI / 180., 0.));
+
+        let bounds = sdf.get_bounds(&None);
+
+        assert_float_absolute_eq!(bounds.0.x, -(2f32.sqrt()), 1e-5);
+        assert_float_absolute_eq!(bounds.0.y, -1., 1e-5);
+        assert_float_absolute_eq!(bounds.0.z, -(2f32.sqrt()), 1e-5);
+        assert_float_absolute_eq!(bounds.1.x, 2f32.sqrt(), 1e-5);
+        assert_float_absolute_eq!(bounds.1.y, 1., 1e-5);
+        assert_float_absolute_eq!(bounds.1.z, 2f32.sqrt(), 1e-5);
+    }
+
+    #[test]
+    fn rotates_infinite_bounds() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::InfiniteCylinder(1.))
+            .with_rotation(Quat::from_euler(EulerRot::XYZ, 0., 0., 90. * PI / 180.))
+            .with_translation(Vec3::Y);
+
+        let bounds = sdf.get_bounds(&None);
+
+        assert_eq!(bounds.0.x, f32::NEG_INFINITY);
+        assert_float_absolute_eq!(bounds.0.y, 0.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_eq!(bounds.1.x, f32::INFINITY);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn rounds_bounds() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Box(Vec3::new(1., 2., 0.5)))
+            .with_rounding(0.5)
+            .with_translation(Vec3::X);
+
+        let bounds = sdf.get_bounds(&None);
+
+        assert_float_absolute_eq!(bounds.0.x, -0.5);
+        assert_float_absolute_eq!(bounds.0.y, -2.5);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 2.5);
+        assert_float_absolute_eq!(bounds.1.y, 2.5);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn onion_bounds() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Box(Vec3::new(1., 2., 0.5)))
+            .with_scale(2.)
+            .with_modifier(SDFModifier::Onion(0.5));
+
+        let bounds = sdf.get_bounds(&None);
+
+        assert_float_absolute_eq!(bounds.0.x, -2.5);
+        assert_float_absolute_eq!(bounds.0.y, -4.5);
+        assert_float_absolute_eq!(bounds.0.z, -1.5);
+        assert_float_absolute_eq!(bounds.1.x, 2.5);
+        assert_float_absolute_eq!(bounds.1.y, 4.5);
+        assert_float_absolute_eq!(bounds.1.z, 1.5);
+    }
+
     #[test]
     fn scales_bounds() {
         let sdf = SDFElement::default().with_scale(2.);
@@ -691,7 +1316,7 @@ mod tests {
         let sdf_a = SDFElement::default().with_translation(Vec3::X);
         let sdf_b = SDFElement::default().with_translation(-1. * Vec3::X);
         let sdf = SDFObject {
-            elements: vec![sdf_a, sdf_b],
+            nodes: vec![sdf_a.into(), sdf_b.into()],
             mesh_handle: None,
             image_handle: None,
             material_handle: None,
@@ -707,11 +1332,571 @@ mod tests {
         assert_float_absolute_eq!(bounds.1.z, 1.);
     }
 
+    #[test]
+    fn subtraction_only_applies_within_a_group() {
+        let sdf = SDFObject::default()
+            .with_element(SDFElement::default().with_translation(Vec3::NEG_X))
+            .with_group(
+                SDFGroup::default()
+                    .with_element(SDFElement::default().with_translation(Vec3::X))
+                    .with_element(
+                        SDFElement::default()
+                            .with_primitive(SDFPrimitive::Sphere(0.75))
+                            .with_operation(SDFOperators::Subtraction),
+                    ),
+            );
+
+        let kept = sdf.value_at_point(&Vec3::new(-0.5, 0., 0.));
+        let removed = sdf.value_at_point(&Vec3::new(0.5, 0., 0.));
+
+        assert_float_absolute_eq!(kept, -0.5);
+        assert_float_absolute_eq!(removed, 0.25);
+    }
+
+    #[test]
+    fn empty_groups_are_skipped() {
+        let sdf = SDFObject::default()
+            .with_element(SDFElement::default())
+            .with_group(SDFGroup::new().with_operation(SDFOperators::Intersection))
+            .with_group(
+                SDFGroup::new()
+                    .with_group(SDFGroup::new())
+                    .with_operation(SDFOperators::SmoothIntersection(0.5)),
+            );
+
+        assert_eq!(sdf.nodes.len(), 1);
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::ZERO), -1.);
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::new(0., 3., 0.)), 2.);
+        assert_eq!(sdf.get_bounds(), (Vec3::NEG_ONE, Vec3::ONE));
+        assert_eq!(sdf.get_bake_bounds(), (Vec3::NEG_ONE, Vec3::ONE));
+    }
+
+    #[test]
+    fn nested_empty_groups_are_skipped() {
+        let mut sdf = SDFObject::default().with_element(SDFElement::default());
+        sdf.nodes.push(SDFNode::Group(SDFGroup {
+            children: vec![SDFNode::Group(SDFGroup::new())],
+            operation: SDFOperators::Intersection,
+            ..default()
+        }));
+
+        assert!(sdf.nodes[1].is_empty());
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::ZERO), -1.);
+        assert_eq!(sdf.get_bounds(), (Vec3::NEG_ONE, Vec3::ONE));
+    }
+
+    #[test]
+    fn transforms_a_group() {
+        let sdf = SDFObject::default().with_group(
+            SDFGroup::new()
+                .with_element(SDFElement::default().with_translation(Vec3::X))
+                .with_translation(Vec3::Y * 2.)
+                .with_rotation(Quat::from_rotation_y(PI))
+                .with_scale(2.),
+        );
+
+        let interior = sdf.value_at_point(&Vec3::new(-2., 2., 0.));
+        let outside = sdf.value_at_point(&Vec3::new(-5., 2., 0.));
+        assert_float_absolute_eq!(interior, -2., 1e-5);
+        assert_float_absolute_eq!(outside, 1., 1e-5);
+
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -4., 1e-5);
+        assert_float_absolute_eq!(bounds.0.y, 0., 1e-5);
+        assert_float_absolute_eq!(bounds.0.z, -2., 1e-5);
+        assert_float_absolute_eq!(bounds.1.x, 0., 1e-5);
+        assert_float_absolute_eq!(bounds.1.y, 4., 1e-5);
+        assert_float_absolute_eq!(bounds.1.z, 2., 1e-5);
+    }
+
+    #[test]
+    fn bake_bounds_clip_unbounded_groups() {
+        let sdf = SDFObject::default()
+            .with_element(SDFElement::default())
+            .with_group(
+                SDFGroup::new()
+                    .with_element(
+                        SDFElement::default().with_primitive(SDFPrimitive::InfiniteCylinder(1.)),
+                    )
+                    .with_translation(Vec3::X * 3.),
+            );
+
+        let bounds = sdf.get_bounds();
+        assert_eq!(bounds.0.y, f32::NEG_INFINITY);
+        assert_eq!(bounds.1.y, f32::INFINITY);
+
+        let bounds = sdf.get_bake_bounds();
+        assert_eq!(bounds, (Vec3::NEG_ONE, Vec3::new(4., 1., 1.)));
+    }
+
+    #[test]
+    fn morphs_between_objects() {
+        let a = SDFObject::default().with_element(SDFElement::default().with_translation(Vec3::X));
+        let b = SDFObject::default()
+            .with_element(SDFElement::default().with_primitive(SDFPrimitive::Box(Vec3::ONE)))
+            .with_element(
+                SDFElement::default()
+                    .with_primitive(SDFPrimitive::Sphere(0.5))
+                    .with_operation(SDFOperators::Subtraction),
+            );
+        let point = Vec3::new(0.25, 0.5, 0.);
+
+        for t in [0., 0.4, 1.] {
+            let value = SDFObject::morph(&a, &b, t).value_at_point(&point);
+            let expected = a.value_at_point(&point) * (1. - t) + b.value_at_point(&point) * t;
+            assert_float_absolute_eq!(value, expected);
+        }
+
+        let morph = SDFObject::morph(&a, &b, 0.4);
+        let bounds = morph.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+
+        let (mesh, _) = morph.generate_mesh_and_texture(4, 1, 0.1);
+        assert!(mesh.count_vertices() > 0);
+    }
+
+    #[test]
+    fn morphing_with_an_empty_object_uses_the_other_object() {
+        let empty = SDFObject::default();
+        let sphere =
+            SDFObject::default().with_element(SDFElement::default().with_translation(Vec3::X));
+        let point = Vec3::new(0.5, 0.25, 0.);
+
+        for morph in [
+            SDFObject::morph(&empty, &sphere, 0.5),
+            SDFObject::morph(&sphere, &empty, 0.5),
+        ] {
+            assert_float_absolute_eq!(morph.value_at_point(&point), sphere.value_at_point(&point));
+            assert_eq!(morph.get_bounds(), sphere.get_bounds());
+            let (mesh, _) = morph.generate_mesh_and_texture(4, 1, 0.1);
+            assert!(mesh.count_vertices() > 0);
+        }
+    }
+
+    #[test]
+    fn intersecting_a_plane_clips_bounds() {
+        let sdf = SDFObject::default()
+            .with_element(SDFElement::default())
+            .with_element(
+                SDFElement::default()
+                    .with_primitive(SDFPrimitive::Plane {
+                        normal: Vec3::Y,
+                        offset: 0.,
+                    })
+                    .with_operation(SDFOperators::Intersection),
+            );
+
+        let bounds = sdf.get_bake_bounds();
+
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 0.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn bake_bounds_clip_unbounded_objects() {
+        let sdf = SDFObject::default()
+            .with_element(
+                SDFElement::default()
+                    .with_primitive(SDFPrimitive::Plane {
+                        normal: Vec3::Y,
+                        offset: 2.,
+                    })
+                    .with_translation(Vec3::Y),
+            )
+            .with_element(SDFElement::default().with_translation(Vec3::X));
+
+        let bounds = sdf.get_bounds();
+        assert_eq!(bounds.0, Vec3::splat(f32::NEG_INFINITY));
+        assert_eq!(bounds.1.x, f32::INFINITY);
+        assert_float_absolute_eq!(bounds.1.y, 3.);
+        assert_eq!(bounds.1.z, f32::INFINITY);
+
+        let bounds = sdf.get_bake_bounds();
+        assert_float_absolute_eq!(bounds.0.x, 0.);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 3.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn repeats_a_sdf_in_a_fence() {
+        let sdf = SDFObject::default().with_element(
+            SDFElement::default()
+                .with_primitive(SDFPrimitive::Box(Vec3::new(0.25, 1., 0.25)))
+                .with_modifier(SDFModifier::RepeatInfinite(Vec3::new(4., 0., 0.)))
+                .with_scale(2.),
+        );
+
+        let post = sdf.value_at_point(&Vec3::new(40., 0., 0.));
+        let gap = sdf.value_at_point(&Vec3::new(-6., 0., 0.));
+        let above = sdf.value_at_point(&Vec3::new(12., 3., 0.));
+
+        assert_float_absolute_eq!(post, -0.5);
+        assert_float_absolute_eq!(gap, 1.5);
+        assert_float_absolute_eq!(above, 1.);
+    }
+
+    #[test]
+    fn repeats_a_sdf_in_a_polar_colonnade() {
+        let sdf = SDFObject::default().with_element(
+            SDFElement::default()
+                .with_primitive(SDFPrimitive::Sphere(0.5))
+                .with_translation(Vec3::new(3., 0., 0.))
+                .with_modifier(SDFModifier::RepeatPolar(6)),
+        );
+
+        for copy in 0..6 {
+            let rotation = Quat::from_rotation_y(copy as f32 * PI / 3.);
+            let value = sdf.value_at_point(&rotation.mul_vec3(Vec3::new(3., 0.5, 0.)));
+            assert_float_absolute_eq!(value, 0., 1e-5);
+        }
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::ZERO), 2.5, 1e-5);
+    }
+
+    #[test]
+    fn mirrors_a_sdf() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Sphere(0.5))
+            .with_translation(Vec3::new(2., 1., 0.))
+            .with_modifier(SDFModifier::Mirror(BVec3::new(true, false, false)));
+
+        let right = sdf.value_at_point(&Vec3::new(2., 1., 0.));
+        let left = sdf.value_at_point(&Vec3::new(-2., 1., 0.));
+        let below = sdf.value_at_point(&Vec3::new(-2., -1., 0.));
+
+        assert_float_absolute_eq!(right, -0.5);
+        assert_float_absolute_eq!(left, -0.5);
+        assert_float_absolute_eq!(below, 1.5);
+
+        let bounds = sdf.get_bounds(&None);
+        assert_float_absolute_eq!(bounds.0.x, -2.5);
+        assert_float_absolute_eq!(bounds.0.y, 0.5);
+        assert_float_absolute_eq!(bounds.1.x, 2.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.5);
+    }
+
+    #[test]
+    fn twisted_box_never_overestimates() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Box(Vec3::new(1., 2., 0.25)))
+            .with_modifier(SDFModifier::Twist(0.8));
+        let surface = (0..=40)
+            .flat_map(|y| (0..=40).map(move |x| Vec2::new(x as f32, y as f32) / 20. - 1.))
+            .flat_map(|face| {
+                [
+                    Vec3::new(face.x, face.y * 2., 0.25),
+                    Vec3::new(face.x, face.y * 2., -0.25),
+                    Vec3::new(1., face.y * 2., face.x * 0.25),
+                    Vec3::new(-1., face.y * 2., face.x * 0.25),
+                ]
+            })
+            .map(|point| {
+                let xz = Vec2::from_angle(-0.8 * point.y).rotate(point.xz());
+                Vec3::new(xz.x, point.y, xz.y)
+            })
+            .collect::<Vec<_>>();
+
+        for x in -6..=6 {
+            for y in -6..=6 {
+                let point = Vec3::new(x as f32, y as f32, x as f32 * 0.7) * 0.4;
+                let closest = surface
+                    .iter()
+                    .map(|surface| surface.distance(point))
+                    .fold(f32::INFINITY, f32::min);
+                let value = sdf.value_at_point(&point);
+                assert!(value.abs() <= closest + 1e-4);
+                assert_float_absolute_eq!(
+                    sdf.value_at_point(&surface[(x * 13 + y + 200) as usize]),
+                    0.,
+                    1e-5
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn twists_a_transformed_element_in_place() {
+        let twisted = || {
+            SDFElement::default()
+                .with_primitive(SDFPrimitive::Box(Vec3::new(1., 2., 0.25)))
+                .with_modifier(SDFModifier::Twist(0.8))
+        };
+        let local = twisted();
+        let sdf = twisted()
+            .with_translation(Vec3::new(5., 1., -3.))
+            .with_rotation(Quat::from_rotation_z(PI / 2.))
+            .with_scale(2.);
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::splat(2.),
+            Quat::from_rotation_z(PI / 2.),
+            Vec3::new(5., 1., -3.),
+        );
+
+        for x in -6..=6 {
+            for y in -6..=6 {
+                let point = Vec3::new(x as f32, y as f32, x as f32 * 0.7) * 0.4;
+                let value = sdf.value_at_point(&transform.transform_point3(point));
+                assert_float_absolute_eq!(value, local.value_at_point(&point) * 2., 1e-4);
+            }
+        }
+
+        let bounds = sdf.get_bounds(&None);
+        let radius = Vec2::new(1., 0.25).length() * 2.;
+        assert_float_absolute_eq!(bounds.0.x, 1., 1e-4);
+        assert_float_absolute_eq!(bounds.1.x, 9., 1e-4);
+        assert_float_absolute_eq!(bounds.0.y, 1. - radius, 1e-4);
+        assert_float_absolute_eq!(bounds.1.z, -3. + radius, 1e-4);
+    }
+
+    #[test]
+    fn tapered_cone_is_empty_above_apex() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::CappedCylinder {
+                radius: 1.,
+                half_height: 1.,
+            })
+            .with_modifier(SDFModifier::Taper(-0.5));
+
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::new(0., 3., 0.)), 2.);
+        for y in [1.5, 2., 2.5, 4.] {
+            let point = Vec3::new(0.75, y, -0.5);
+            let value = sdf.value_at_point(&point);
+            assert!(value > 0.);
+            assert!(value <= (point - Vec3::new(0., 1., 0.)).length());
+        }
+    }
+
+    #[test]
+    fn twisted_repetition_stays_finite() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Box(Vec3::new(0.5, 1., 0.5)))
+            .with_modifier(SDFModifier::Twist(0.5))
+            .with_modifier(SDFModifier::RepeatInfinite(Vec3::new(4., 0., 4.)));
+
+        let bounds = sdf.get_bounds(&None);
+        assert_eq!(bounds.0.x, f32::NEG_INFINITY);
+        assert_eq!(bounds.1.z, f32::INFINITY);
+
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::new(40.5, 0., 0.)), 0.);
+        let inside = sdf.value_at_point(&Vec3::ZERO);
+        assert_float_absolute_eq!(inside, -0.5 / (1. + 0.5 * 0.5f32.sqrt()));
+        let between = sdf.value_at_point(&Vec3::new(2., 0., 2.));
+        assert!(between > 0.1 && between <= 2f32.sqrt() * 1.5);
+        let above = sdf.value_at_point(&Vec3::new(0., 3., 0.));
+        assert!(above > 0.1 && above <= 2.);
+    }
+
+    #[test]
+    fn tapers_a_cylinder_into_a_cone() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::CappedCylinder {
+                radius: 1.,
+                half_height: 1.,
+            })
+            .with_modifier(SDFModifier::Taper(-0.5));
+
+        let base = sdf.value_at_point(&Vec3::new(1.5, -1., 0.));
+        let top = sdf.value_at_point(&Vec3::new(0., 1., 0.5));
+        let outside = sdf.value_at_point(&Vec3::new(2., 1., 0.));
+
+        assert_float_absolute_eq!(base, 0.);
+        assert_float_absolute_eq!(top, 0.);
+        assert!(outside > 0. && outside <= 1.5);
+
+        let bounds = sdf.get_bounds(&None);
+        assert_float_absolute_eq!(bounds.0.x, -1.5);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_float_absolute_eq!(bounds.1.z, 1.5);
+    }
+
+    #[test]
+    fn bend_bounds_cover_the_bent_element() {
+        let sdf = SDFElement::default()
+            .with_primitive(SDFPrimitive::Box(Vec3::new(3., 0.5, 0.5)))
+            .with_modifier(SDFModifier::Bend(0.3));
+
+        let bounds = sdf.get_bounds(&None);
+        let radius = Vec2::new(3., 0.5).length();
+        assert_float_absolute_eq!(bounds.0.x, -radius);
+        assert_float_absolute_eq!(bounds.0.y, -radius);
+        assert_float_absolute_eq!(bounds.0.z, -0.5);
+        assert_float_absolute_eq!(bounds.1.x, radius);
+        assert_float_absolute_eq!(bounds.1.y, radius);
+        assert_float_absolute_eq!(bounds.1.z, 0.5);
+    }
+
+    #[test]
+    fn displaces_a_sdf_reproducibly() {
+        let rock = || {
+            SDFObject::default().with_element(SDFElement::default().with_scale(2.).with_modifier(
+                SDFModifier::Displace(SDFNoise::new().with_seed(9).with_amplitude(0.3)),
+            ))
+        };
+        let noise = SDFNoise::new().with_seed(9).with_amplitude(0.3);
+        let point = Vec3::new(1.5, 0.5, -1.);
+
+        let value = rock().value_at_point(&point);
+        let expected = (point.length() - 2. + noise.value_at_point(point / 2.) * 2.)
+            / (1. + noise.lipschitz());
+        assert_float_absolute_eq!(value, expected);
+        let far = rock().value_at_point(&Vec3::new(6., 0., 0.));
+        assert_float_absolute_eq!(far, 4. - 1.125);
+
+        let bounds = rock().get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -3.125);
+        assert_float_absolute_eq!(bounds.1.y, 3.125);
+
+        let texture = rock().generate_texture(4, &bounds);
+        assert_eq!(texture, rock().generate_texture(4, &bounds));
+    }
+
+    #[test]
+    fn displacement_moves_with_the_element() {
+        let rock = || {
+            SDFElement::default()
+                .with_modifier(SDFModifier::Displace(
+                    SDFNoise::new().with_seed(4).with_amplitude(0.3),
+                ))
+                .with_scale(1.5)
+        };
+        let offset = Vec3::new(5.3, -1.1, 2.);
+        let rotation = Quat::from_rotation_y(0.7);
+        let moved = rock().with_translation(offset).with_rotation(rotation);
+        let repeated = rock().with_modifier(SDFModifier::RepeatInfinite(Vec3::new(6., 0., 0.)));
+
+        for point in sample_points(5, Vec3::splat(0.6), Vec3::splat(-1.2)) {
+            let value = rock().value_at_point(&point);
+            let moved_point = rotation.mul_vec3(point) + offset;
+            assert_float_absolute_eq!(moved.value_at_point(&moved_point), value, 1e-5);
+            let copy = point + Vec3::new(12., 0., 0.);
+            assert_float_absolute_eq!(repeated.value_at_point(&copy), value, 1e-5);
+        }
+    }
+
+    #[test]
+    fn limited_repetition_bounds() {
+        let sdf = SDFElement::default()
+            .with_modifier(SDFModifier::RepeatLimited {
+                spacing: Vec3::new(3., 0., 4.),
+                count: UVec3::new(4, 1, 2),
+            })
+            .with_translation(Vec3::Y);
+
+        let bounds = sdf.get_bounds(&None);
+        assert_float_absolute_eq!(bounds.0.x, -5.5);
+        assert_float_absolute_eq!(bounds.0.y, 0.);
+        assert_float_absolute_eq!(bounds.0.z, -3.);
+        assert_float_absolute_eq!(bounds.1.x, 5.5);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 3.);
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::new(4.5, 1., 2.)), -1.);
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::new(8.5, 1., 2.)), 3.);
+    }
+
+    #[test]
+    fn bake_bounds_clip_infinite_repetition() {
+        let sdf = SDFObject::default().with_element(
+            SDFElement::default()
+                .with_modifier(SDFModifier::RepeatInfinite(Vec3::new(3., 0., 3.)))
+                .with_translation(Vec3::Y),
+        );
+
+        let bounds = sdf.get_bounds();
+        assert_eq!(bounds.0.x, f32::NEG_INFINITY);
+        assert_float_absolute_eq!(bounds.0.y, 0.);
+        assert_eq!(bounds.1.z, f32::INFINITY);
+
+        let bounds = sdf.get_bake_bounds();
+        assert_eq!(bounds, (Vec3::new(-1., 0., -1.), Vec3::new(1., 2., 1.)));
+    }
+
+    #[test]
+    fn generate_boxes_on_surface_of_intersected_plane() {
+        let sdf = SDFObject::default()
+            .with_element(SDFElement::default().with_primitive(SDFPrimitive::Box(Vec3::ONE)))
+            .with_element(
+                SDFElement::default()
+                    .with_primitive(SDFPrimitive::Plane {
+                        normal: Vec3::NEG_Y,
+                        offset: 0.,
+                    })
+                    .with_operation(SDFOperators::Intersection),
+            );
+
+        let result = sdf.generate_lod_boxes(2, 1, 0.1);
+        assert_float_absolute_eq!(result[0].0, 1.);
+        assert_eq!(result[0].1[0].len(), 8);
+        assert!(result[0].1[0].iter().all(|b| b.y >= 0.));
+    }
+
+    #[test]
+    fn generate_boxes_on_heightfield_with_cave() {
+        let heightfield = SDFHeightfield::new(2, 2, &[1.; 4], Vec2::new(2., 2.), 2.).unwrap();
+        let sdf = SDFObject::default()
+            .with_element(
+                SDFElement::default()
+                    .with_primitive(SDFPrimitive::Heightfield(Arc::new(heightfield))),
+            )
+            .with_element(
+                SDFElement::default()
+                    .with_primitive(SDFPrimitive::Sphere(0.5))
+                    .with_translation(Vec3::Y)
+                    .with_operation(SDFOperators::Subtraction),
+            );
+
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::Y), 0.5);
+
+        let result = sdf.generate_boxes(4, &sdf.get_bake_bounds());
+        assert_float_absolute_eq!(result.0, 0.5);
+        assert_eq!(result.1.len(), 4 * 4 * 4);
+        assert!(result.1.contains(&Vec3::new(0.25, 0.75, 0.25)));
+    }
+
+    struct Cube;
+
+    impl CustomPrimitive for Cube {
+        fn distance(&self, point: Vec3) -> f32 {
+            (point.abs() - Vec3::ONE).max_element()
+        }
+
+        fn bounds(&self) -> (Vec3, Vec3) {
+            (Vec3::NEG_ONE, Vec3::ONE)
+        }
+    }
+
+    #[test]
+    fn generate_boxes_on_custom_primitive() {
+        let sdf = SDFObject::default().with_element(
+            SDFElement::default()
+                .with_primitive(SDFPrimitive::Custom(Arc::new(Cube)))
+                .with_translation(Vec3::X),
+        );
+
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, 0.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::X), -1.);
+
+        let result = sdf.generate_boxes(3, &sdf.get_bake_bounds());
+        assert_float_absolute_eq!(result.0, 2. / 3.);
+        assert_eq!(result.1.len(), 9 * 2 + 8);
+    }
+
     #[test]
     fn generate_boxes_on_surface() {
         let sdf_a = SDFElement::default().with_primitive(SDFPrimitive::Box(Vec3::ONE));
         let sdf = SDFObject {
-            elements: vec![sdf_a],
+            nodes: vec![sdf_a.into()],
             mesh_handle: None,
             image_handle: None,
             material_handle: None,
@@ -726,7 +1911,7 @@ mod tests {
     fn generate_boxes_on_surface_with_lod() {
         let sdf_a = SDFElement::default().with_primitive(SDFPrimitive::Box(Vec3::ONE));
         let sdf = SDFObject {
-            elements: vec![sdf_a],
+            nodes: vec![sdf_a.into()],
             mesh_handle: None,
             image_handle: None,
             material_handle: None,
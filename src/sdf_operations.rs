@@ -1,8 +1,10 @@
 //! Describes the available SDF operations
+use std::f32::consts::{FRAC_1_SQRT_2, SQRT_2};
+
 use bevy::prelude::*;
 
 /// The operations combining SDFs
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SDFOperators {
     /// A hard union between two SDFs
     Union,
@@ -10,6 +12,66 @@ pub enum SDFOperators {
     Subtraction,
     /// A hard intersection between two SDFs
     Intersection,
+    /// A union blending the two SDFs together, provided the blend radius
+    SmoothUnion(f32),
+    /// A subtraction blending the two SDFs together, provided the blend radius - subtracting R from L
+    SmoothSubtraction(f32),
+    /// An intersection blending the two SDFs together, provided the blend radius
+    SmoothIntersection(f32),
+    /// A union with a 45 degree chamfer, provided the chamfer size
+    ChamferUnion(f32),
+    /// A subtraction with a 45 degree chamfer, provided the chamfer size - subtracting R from L
+    ChamferSubtraction(f32),
+    /// An intersection with a 45 degree chamfer, provided the chamfer size
+    ChamferIntersection(f32),
+    /// A union with a staircase where the SDFs meet
+    StairsUnion {
+        /// The size of the staircase
+        radius: f32,
+        /// The number of steps
+        steps: u32,
+    },
+    /// A subtraction with a staircase where the SDFs meet - subtracting R from L
+    StairsSubtraction {
+        /// The size of the staircase
+        radius: f32,
+        /// The number of steps
+        steps: u32,
+    },
+    /// An intersection with a staircase where the SDFs meet
+    StairsIntersection {
+        /// The size of the staircase
+        radius: f32,
+        /// The number of steps
+        steps: u32,
+    },
+    /// A union with a row of rounded columns where the SDFs meet
+    ColumnsUnion {
+        /// The size of the area covered by the columns
+        radius: f32,
+        /// The number of columns
+        steps: u32,
+    },
+    /// A subtraction with a row of rounded grooves where the SDFs meet - subtracting R from L
+    ColumnsSubtraction {
+        /// The size of the area covered by the grooves
+        radius: f32,
+        /// The number of grooves
+        steps: u32,
+    },
+    /// An intersection with a row of rounded grooves where the SDFs meet
+    ColumnsIntersection {
+        /// The size of the area covered by the grooves
+        radius: f32,
+        /// The number of grooves
+        steps: u32,
+    },
+    /// A linear interpolation from L to R, provided how far along it is between 0 and 1
+    ///
+    /// The bounds cover both SDFs, so the morph can be animated without changing them.
+    /// An infinite value on either side is treated as nothing to morph from or to,
+    /// and the other side is used as it is.
+    Morph(f32),
 }
 
 impl SDFOperators {
@@ -19,15 +81,61 @@ impl SDFOperators {
             SDFOperators::Union => union(left, right),
             SDFOperators::Subtraction => subtraction(left, right),
             SDFOperators::Intersection => intersection(left, right),
+            SDFOperators::SmoothUnion(k) => smooth_min(*left, *right, *k),
+            SDFOperators::SmoothSubtraction(k) => -smooth_min(-left, *right, *k),
+            SDFOperators::SmoothIntersection(k) => -smooth_min(-left, -right, *k),
+            SDFOperators::ChamferUnion(radius) => chamfer_union(*left, *right, *radius),
+            SDFOperators::ChamferSubtraction(radius) => -chamfer_union(-left, *right, *radius),
+            SDFOperators::ChamferIntersection(radius) => -chamfer_union(-left, -right, *radius),
+            SDFOperators::StairsUnion { radius, steps } => {
+                stairs_union(*left, *right, *radius, *steps)
+            }
+            SDFOperators::StairsSubtraction { radius, steps } => {
+                -stairs_union(-left, *right, *radius, *steps)
+            }
+            SDFOperators::StairsIntersection { radius, steps } => {
+                -stairs_union(-left, -right, *radius, *steps)
+            }
+            SDFOperators::ColumnsUnion { radius, steps } => {
+                columns_union(*left, *right, *radius, *steps)
+            }
+            SDFOperators::ColumnsSubtraction { radius, steps } => {
+                columns_subtraction(*left, *right, *radius, *steps)
+            }
+            SDFOperators::ColumnsIntersection { radius, steps } => {
+                columns_subtraction(*left, -right, *radius, *steps)
+            }
+            SDFOperators::Morph(t) => morph(*left, *right, *t),
         }
     }
 
     /// Process the bounds of two SDFs
     pub fn get_bounds(&self, left: &(Vec3, Vec3), right: &(Vec3, Vec3)) -> (Vec3, Vec3) {
         match self {
-            SDFOperators::Union => (left.0.min(right.0), left.1.max(right.1)),
+            SDFOperators::Union | SDFOperators::Morph(_) => {
+                (left.0.min(right.0), left.1.max(right.1))
+            }
             SDFOperators::Subtraction => (left.0, left.1),
             SDFOperators::Intersection => (left.0.max(right.0), left.1.min(right.1)),
+            // Blended unions only add material within the blend radius of both SDFs
+            SDFOperators::SmoothUnion(radius)
+            | SDFOperators::ChamferUnion(radius)
+            | SDFOperators::StairsUnion { radius, .. }
+            | SDFOperators::ColumnsUnion { radius, .. } => {
+                let radius = radius.max(0.);
+                (left.0.min(right.0) - radius, left.1.max(right.1) + radius)
+            }
+            // Blended subtractions and intersections only ever remove material
+            SDFOperators::SmoothSubtraction(_)
+            | SDFOperators::ChamferSubtraction(_)
+            | SDFOperators::StairsSubtraction { .. }
+            | SDFOperators::ColumnsSubtraction { .. } => (left.0, left.1),
+            SDFOperators::SmoothIntersection(_)
+            | SDFOperators::ChamferIntersection(_)
+            | SDFOperators::StairsIntersection { .. }
+            | SDFOperators::ColumnsIntersection { .. } => {
+                (left.0.max(right.0), left.1.min(right.1))
+            }
         }
     }
 }
@@ -44,6 +152,90 @@ fn intersection(left: &f32, right: &f32) -> f32 {
     left.max(*right)
 }
 
+/// Linearly interpolate between two SDFs, with `t` clamped between 0 and 1
+///
+/// If either side is infinite, the other side is returned as it is
+fn morph(left: f32, right: f32, t: f32) -> f32 {
+    if left == f32::INFINITY {
+        return right;
+    }
+    if right == f32::INFINITY {
+        return left;
+    }
+    let t = t.clamp(0., 1.);
+    left * (1. - t) + right * t
+}
+
+/// A polynomial smooth minimum, which is at most `k / 4` below the hard minimum
+///
+/// If either side is infinite, there is nothing to blend with, so the hard minimum is returned
+fn smooth_min(left: f32, right: f32, k: f32) -> f32 {
+    if k <= 0. || left.is_infinite() || right.is_infinite() {
+        return left.min(right);
+    }
+    let h = (0.5 + 0.5 * (right - left) / k).clamp(0., 1.);
+    right + (left - right) * h - k * h * (1. - h)
+}
+
+/// A union cut by a 45 degree plane where the SDFs are within the radius of each other
+fn chamfer_union(left: f32, right: f32, radius: f32) -> f32 {
+    left.min(right).min((left + right - radius) * FRAC_1_SQRT_2)
+}
+
+/// A union filled in with a staircase, producing `steps - 1` steps
+fn stairs_union(left: f32, right: f32, radius: f32, steps: u32) -> f32 {
+    let step = radius / steps.max(1) as f32;
+    let u = right - radius;
+    let stairs = 0.5 * (u + left + ((u - left + step).rem_euclid(2. * step) - step).abs());
+    left.min(right).min(stairs)
+}
+
+/// Rotate the pair of distances by 45 degrees, placing the line where they're equal on the X axis
+fn rotate_45(point: Vec2) -> Vec2 {
+    (point + Vec2::new(point.y, -point.x)) * FRAC_1_SQRT_2
+}
+
+/// Repeat a value within cells of the given size, centered on 0
+fn repeat(value: f32, size: f32) -> f32 {
+    (value + size / 2.).rem_euclid(size) - size / 2.
+}
+
+fn column_radius(radius: f32, steps: u32) -> f32 {
+    radius * SQRT_2 / ((steps.max(1) - 1) as f32 * 2. + SQRT_2)
+}
+
+/// A union filled in with a row of columns along the line where the SDFs meet
+fn columns_union(left: f32, right: f32, radius: f32, steps: u32) -> f32 {
+    if left >= radius || right >= radius {
+        return left.min(right);
+    }
+    let column = column_radius(radius, steps);
+    let mut p = rotate_45(Vec2::new(left, right));
+    p.x += column * SQRT_2 - FRAC_1_SQRT_2 * radius;
+    if steps % 2 == 1 {
+        p.y += column;
+    }
+    p.y = repeat(p.y, column * 2.);
+    (p.length() - column).min(p.x).min(left).min(right)
+}
+
+/// A subtraction carving a row of grooves along the line where the SDFs meet
+fn columns_subtraction(left: f32, right: f32, radius: f32, steps: u32) -> f32 {
+    let left = -left;
+    if left >= radius || right >= radius {
+        return -left.min(right);
+    }
+    let column = column_radius(radius, steps);
+    let mut p = rotate_45(Vec2::new(left, right));
+    p.y += column;
+    p.x -= FRAC_1_SQRT_2 * radius + column * FRAC_1_SQRT_2;
+    if steps % 2 == 1 {
+        p.y += column;
+    }
+    p.y = repeat(p.y, column * 2.);
+    -(column - p.length()).max(p.x).min(left).min(right)
+}
+
 #[cfg(test)]
 mod test {
     use assert_float_eq::*;
@@ -147,4 +339,247 @@ mod test {
         assert_float_absolute_eq!(bounds.1.y, 0.);
         assert_float_absolute_eq!(bounds.1.z, 0.5);
     }
+
+    #[test]
+    pub fn smooth_union_blends_close_values() {
+        let result = SDFOperators::SmoothUnion(1.).value(&0.5, &0.5);
+        assert_float_absolute_eq!(result, 0.25);
+    }
+
+    #[test]
+    pub fn smooth_union_gets_minimum_of_distant_values() {
+        let result = SDFOperators::SmoothUnion(1.).value(&2., &0.5);
+        assert_float_absolute_eq!(result, 0.5);
+    }
+
+    #[test]
+    pub fn smooth_union_without_radius_gets_minimum() {
+        let result = SDFOperators::SmoothUnion(0.).value(&0.5, &0.5);
+        assert_float_absolute_eq!(result, 0.5);
+    }
+
+    #[test]
+    pub fn smooth_union_with_nothing_gets_other_value() {
+        let left = SDFOperators::SmoothUnion(0.5).value(&f32::INFINITY, &1.);
+        let right = SDFOperators::SmoothUnion(0.5).value(&1., &f32::INFINITY);
+        assert_float_absolute_eq!(left, 1.);
+        assert_float_absolute_eq!(right, 1.);
+    }
+
+    #[test]
+    pub fn smooth_subtraction_blends_close_values() {
+        let result = SDFOperators::SmoothSubtraction(1.).value(&-0.5, &0.5);
+        assert_float_absolute_eq!(result, -0.25);
+    }
+
+    #[test]
+    pub fn smooth_subtraction_gets_max_of_distant_values() {
+        let result = SDFOperators::SmoothSubtraction(1.).value(&2., &1.);
+        assert_float_absolute_eq!(result, 2.);
+    }
+
+    #[test]
+    pub fn smooth_intersection_blends_close_values() {
+        let result = SDFOperators::SmoothIntersection(1.).value(&-0.5, &-0.5);
+        assert_float_absolute_eq!(result, -0.25);
+    }
+
+    #[test]
+    pub fn smooth_intersection_gets_max_of_distant_values() {
+        let result = SDFOperators::SmoothIntersection(1.).value(&-2., &1.);
+        assert_float_absolute_eq!(result, 1.);
+    }
+
+    #[test]
+    pub fn smooth_union_bounds_grow_by_blend_radius() {
+        let bounds = SDFOperators::SmoothUnion(0.5).get_bounds(
+            &(Vec3::new(-1., -2., -0.5), Vec3::new(1., 0., 0.5)),
+            &(Vec3::new(0., -1., -1.5), Vec3::new(1.5, 2., 0.5)),
+        );
+
+        assert_float_absolute_eq!(bounds.0.x, -1.5);
+        assert_float_absolute_eq!(bounds.0.y, -2.5);
+        assert_float_absolute_eq!(bounds.0.z, -2.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 2.5);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    pub fn smooth_subtraction_and_intersection_bounds_match_hard_bounds() {
+        let left = (Vec3::new(-1., -2., -0.5), Vec3::new(1., 0., 0.5));
+        let right = (Vec3::new(0., -1., -1.5), Vec3::new(1.5, 2., 0.5));
+
+        assert_eq!(
+            SDFOperators::SmoothSubtraction(0.5).get_bounds(&left, &right),
+            SDFOperators::Subtraction.get_bounds(&left, &right)
+        );
+        assert_eq!(
+            SDFOperators::SmoothIntersection(0.5).get_bounds(&left, &right),
+            SDFOperators::Intersection.get_bounds(&left, &right)
+        );
+    }
+
+    #[test]
+    pub fn chamfer_union_cuts_between_close_values() {
+        let result = SDFOperators::ChamferUnion(2.).value(&0.5, &0.5);
+        assert_float_absolute_eq!(result, -FRAC_1_SQRT_2);
+    }
+
+    #[test]
+    pub fn chamfer_union_gets_minimum_of_distant_values() {
+        let result = SDFOperators::ChamferUnion(0.5).value(&2., &0.5);
+        assert_float_absolute_eq!(result, 0.5);
+    }
+
+    #[test]
+    pub fn chamfer_subtraction_and_intersection_cut_corners() {
+        let subtraction = SDFOperators::ChamferSubtraction(2.).value(&-0.5, &0.5);
+        let intersection = SDFOperators::ChamferIntersection(2.).value(&-0.5, &-0.5);
+
+        assert_float_absolute_eq!(subtraction, FRAC_1_SQRT_2);
+        assert_float_absolute_eq!(intersection, FRAC_1_SQRT_2);
+    }
+
+    #[test]
+    pub fn stairs_union_fills_between_close_values() {
+        let operator = SDFOperators::StairsUnion {
+            radius: 1.,
+            steps: 2,
+        };
+
+        assert_float_absolute_eq!(operator.value(&0.2, &0.2), -0.3);
+        assert_float_absolute_eq!(operator.value(&2., &3.), 2.);
+    }
+
+    #[test]
+    pub fn stairs_subtraction_and_intersection_cut_steps() {
+        let subtraction = SDFOperators::StairsSubtraction {
+            radius: 1.,
+            steps: 2,
+        }
+        .value(&-0.2, &0.2);
+        let intersection = SDFOperators::StairsIntersection {
+            radius: 1.,
+            steps: 2,
+        }
+        .value(&-0.2, &-0.2);
+
+        assert_float_absolute_eq!(subtraction, 0.3);
+        assert_float_absolute_eq!(intersection, 0.3);
+    }
+
+    #[test]
+    pub fn columns_union_fills_between_close_values() {
+        let operator = SDFOperators::ColumnsUnion {
+            radius: 1.,
+            steps: 3,
+        };
+
+        assert_float_absolute_eq!(operator.value(&0.2, &0.2), -0.054_866);
+        assert_float_absolute_eq!(operator.value(&2., &3.), 2.);
+    }
+
+    #[test]
+    pub fn columns_subtraction_and_intersection_cut_grooves() {
+        let subtraction = SDFOperators::ColumnsSubtraction {
+            radius: 1.,
+            steps: 3,
+        }
+        .value(&-0.2, &0.2);
+        let intersection = SDFOperators::ColumnsIntersection {
+            radius: 1.,
+            steps: 3,
+        }
+        .value(&-0.2, &-0.2);
+
+        assert_float_absolute_eq!(subtraction, 0.347_759);
+        assert_float_absolute_eq!(intersection, 0.347_759);
+    }
+
+    #[test]
+    pub fn blended_union_bounds_grow_by_radius() {
+        let left = (Vec3::new(-1., -2., -0.5), Vec3::new(1., 0., 0.5));
+        let right = (Vec3::new(0., -1., -1.5), Vec3::new(1.5, 2., 0.5));
+
+        for operator in [
+            SDFOperators::ChamferUnion(0.5),
+            SDFOperators::StairsUnion {
+                radius: 0.5,
+                steps: 3,
+            },
+            SDFOperators::ColumnsUnion {
+                radius: 0.5,
+                steps: 3,
+            },
+        ] {
+            let bounds = operator.get_bounds(&left, &right);
+            assert_float_absolute_eq!(bounds.0.x, -1.5);
+            assert_float_absolute_eq!(bounds.0.y, -2.5);
+            assert_float_absolute_eq!(bounds.0.z, -2.);
+            assert_float_absolute_eq!(bounds.1.x, 2.);
+            assert_float_absolute_eq!(bounds.1.y, 2.5);
+            assert_float_absolute_eq!(bounds.1.z, 1.);
+        }
+    }
+
+    #[test]
+    pub fn blended_subtraction_and_intersection_bounds_match_hard_bounds() {
+        let left = (Vec3::new(-1., -2., -0.5), Vec3::new(1., 0., 0.5));
+        let right = (Vec3::new(0., -1., -1.5), Vec3::new(1.5, 2., 0.5));
+
+        for operator in [
+            SDFOperators::ChamferSubtraction(0.5),
+            SDFOperators::StairsSubtraction {
+                radius: 0.5,
+                steps: 3,
+            },
+            SDFOperators::ColumnsSubtraction {
+                radius: 0.5,
+                steps: 3,
+            },
+        ] {
+            assert_eq!(
+                operator.get_bounds(&left, &right),
+                SDFOperators::Subtraction.get_bounds(&left, &right)
+            );
+        }
+        for operator in [
+            SDFOperators::ChamferIntersection(0.5),
+            SDFOperators::StairsIntersection {
+                radius: 0.5,
+                steps: 3,
+            },
+            SDFOperators::ColumnsIntersection {
+                radius: 0.5,
+                steps: 3,
+            },
+        ] {
+            assert_eq!(
+                operator.get_bounds(&left, &right),
+                SDFOperators::Intersection.get_bounds(&left, &right)
+            );
+        }
+    }
+
+    #[test]
+    pub fn morph_interpolates_values() {
+        assert_float_absolute_eq!(SDFOperators::Morph(0.25).value(&2., &-2.), 1.);
+        assert_float_absolute_eq!(SDFOperators::Morph(-1.).value(&2., &-2.), 2.);
+        assert_float_absolute_eq!(SDFOperators::Morph(2.).value(&2., &-2.), -2.);
+        assert_float_absolute_eq!(SDFOperators::Morph(1.).value(&f32::INFINITY, &-2.), -2.);
+        assert_float_absolute_eq!(SDFOperators::Morph(0.5).value(&f32::INFINITY, &-2.), -2.);
+        assert_float_absolute_eq!(SDFOperators::Morph(0.5).value(&3., &f32::INFINITY), 3.);
+    }
+
+    #[test]
+    pub fn morph_bounds_encompass_both_bounds() {
+        let left = (Vec3::new(-1., 0., -2.), Vec3::new(1., 1., 0.));
+        let right = (Vec3::new(0., -1., -1.), Vec3::new(2., 0.5, 1.));
+
+        assert_eq!(
+            SDFOperators::Morph(0.3).get_bounds(&left, &right),
+            SDFOperators::Union.get_bounds(&left, &right)
+        );
+    }
 }
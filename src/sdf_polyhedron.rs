@@ -0,0 +1,189 @@
+//! Signed distance fields for convex polyhedra, defined by the planes of their faces
+use bevy::{math::DVec3, prelude::*};
+
+/// The distance used to stand in for infinity while finding the vertices of unbounded polyhedra
+const UNBOUNDED_EXTENT: f64 = 1e7;
+
+/// A convex polyhedron, made of the intersection of the half-spaces below a set of planes
+///
+/// The distance is exact inside and near the faces, and a conservative bound near the edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SDFConvexPolyhedron {
+    /// The normalized normals of the planes, and their distances from the origin
+    planes: Vec<(Vec3, f32)>,
+    /// The bounds, calculated from the vertices
+    bounds: (Vec3, Vec3),
+}
+
+impl SDFConvexPolyhedron {
+    /// Create a polyhedron from a list of planes, provided their normals and their distances
+    /// from the origin
+    ///
+    /// The normals point away from the filled half-spaces, like with
+    /// [`crate::sdf_primitives::SDFPrimitive::Plane`].
+    /// Returns `None` if there are no planes or one of the normals is zero.
+    pub fn new(planes: &[(Vec3, f32)]) -> Option<Self> {
+        if planes.is_empty() {
+            return None;
+        }
+        let planes = planes
+            .iter()
+            .map(|(normal, offset)| {
+                let length = normal.length();
+                (length > 0.).then(|| (*normal / length, *offset / length))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let bounds = polyhedron_bounds(&planes);
+        Some(Self { planes, bounds })
+    }
+
+    /// Get the value of the SDF at a given point
+    pub fn value_at_point(&self, point: Vec3) -> f32 {
+        self.planes
+            .iter()
+            .map(|(normal, offset)| point.dot(*normal) - offset)
+            .fold(f32::NEG_INFINITY, f32::max)
+    }
+
+    /// Get the bounds of the polyhedron
+    ///
+    /// Unbounded axes use infinite values, and an empty polyhedron has empty bounds at the origin
+    pub fn get_bounds(&self) -> (Vec3, Vec3) {
+        self.bounds
+    }
+}
+
+/// Find the bounds of the vertices where any three planes meet within the polyhedron
+///
+/// The polyhedron is clipped by a large box first, so unbounded polyhedra still have vertices -
+/// any that end up on the box are unbounded along that axis.
+fn polyhedron_bounds(planes: &[(Vec3, f32)]) -> (Vec3, Vec3) {
+    let planes = planes
+        .iter()
+        .map(|(normal, offset)| (normal.as_dvec3(), *offset as f64))
+        .chain(
+            [DVec3::X, DVec3::Y, DVec3::Z]
+                .into_iter()
+                .flat_map(|axis| [(axis, UNBOUNDED_EXTENT), (-axis, UNBOUNDED_EXTENT)]),
+        )
+        .collect::<Vec<_>>();
+
+    let mut bounds = (DVec3::splat(f64::INFINITY), DVec3::splat(f64::NEG_INFINITY));
+    for (i, a) in planes.iter().enumerate() {
+        for (j, b) in planes.iter().enumerate().skip(i + 1) {
+            for c in planes.iter().skip(j + 1) {
+                let determinant = a.0.dot(b.0.cross(c.0));
+                if determinant.abs() < 1e-9 {
+                    continue;
+                }
+                let vertex = (b.0.cross(c.0) * a.1 + c.0.cross(a.0) * b.1 + a.0.cross(b.0) * c.1)
+                    / determinant;
+                let inside = planes.iter().all(|(normal, offset)| {
+                    vertex.dot(*normal) - offset <= 1e-6 * (1. + offset.abs())
+                });
+                if inside {
+                    bounds = (bounds.0.min(vertex), bounds.1.max(vertex));
+                }
+            }
+        }
+    }
+
+    if bounds.0.x > bounds.1.x {
+        return (Vec3::ZERO, Vec3::ZERO);
+    }
+    let unbounded = |value: f64| {
+        if value.abs() > UNBOUNDED_EXTENT / 2. {
+            value.signum() as f32 * f32::INFINITY
+        } else {
+            value as f32
+        }
+    };
+    (
+        Vec3::from_array(bounds.0.to_array().map(unbounded)),
+        Vec3::from_array(bounds.1.to_array().map(unbounded)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use super::*;
+
+    fn cube_planes() -> Vec<(Vec3, f32)> {
+        vec![
+            (Vec3::X, 1.),
+            (Vec3::NEG_X, 1.),
+            (Vec3::Y * 2., 2.),
+            (Vec3::NEG_Y, 1.),
+            (Vec3::Z, 1.),
+            (Vec3::NEG_Z, 1.),
+        ]
+    }
+
+    #[test]
+    fn rejects_invalid_planes() {
+        assert!(SDFConvexPolyhedron::new(&[]).is_none());
+        assert!(SDFConvexPolyhedron::new(&[(Vec3::X, 1.), (Vec3::ZERO, 1.)]).is_none());
+    }
+
+    #[test]
+    fn calculates_cube_sdf() {
+        let sdf = SDFConvexPolyhedron::new(&cube_planes()).unwrap();
+
+        let interior = sdf.value_at_point(Vec3::new(0.5, 0., 0.));
+        let surface = sdf.value_at_point(Vec3::Y);
+        let outside = sdf.value_at_point(Vec3::new(0., 0., -1.5));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculates_wedge_sdf() {
+        let mut planes = cube_planes();
+        planes.push((Vec3::new(1., 1., 0.), 0.));
+        let sdf = SDFConvexPolyhedron::new(&planes).unwrap();
+
+        let interior = sdf.value_at_point(Vec3::new(-0.5, -0.5, 0.));
+        let slope = sdf.value_at_point(Vec3::new(0.5, 0.5, 0.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(slope, 2f32.sqrt() / 2.);
+    }
+
+    #[test]
+    fn calculates_wedge_bounds() {
+        let mut planes = cube_planes();
+        planes.push((Vec3::new(1., 2., 0.), -1.));
+        let sdf = SDFConvexPolyhedron::new(&planes).unwrap();
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 0.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn calculates_unbounded_bounds() {
+        let sdf =
+            SDFConvexPolyhedron::new(&[(Vec3::Y, 1.), (Vec3::NEG_Y, 1.), (Vec3::X, 2.)]).unwrap();
+        let bounds = sdf.get_bounds();
+        assert_eq!(bounds.0.x, f32::NEG_INFINITY);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_eq!(bounds.0.z, f32::NEG_INFINITY);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_eq!(bounds.1.z, f32::INFINITY);
+    }
+
+    #[test]
+    fn calculates_empty_bounds() {
+        let sdf = SDFConvexPolyhedron::new(&[(Vec3::Y, -1.), (Vec3::NEG_Y, -1.)]).unwrap();
+        let bounds = sdf.get_bounds();
+        assert_eq!(bounds, (Vec3::ZERO, Vec3::ZERO));
+    }
+}
@@ -1,6 +1,60 @@
 //! Describes the available SDF primitives
 
-use bevy::prelude::Vec3;
+use std::{
+    f32::consts::{FRAC_PI_2, PI},
+    fmt::Debug,
+    sync::Arc,
+};
+
+use bevy::{
+    math::{Vec2Swizzles, Vec3Swizzles},
+    prelude::{Vec2, Vec3},
+};
+
+use crate::{
+    sdf_2d::SDFPrimitive2D, sdf_grid::SDFGrid, sdf_heightfield::SDFHeightfield, sdf_mesh::SDFMesh,
+    sdf_polyhedron::SDFConvexPolyhedron, sdf_spline::SDFSpline, sdf_text::SDFText,
+};
+
+/// The golden ratio, used to construct dodecahedrons and icosahedrons
+const GOLDEN_RATIO: f32 = 1.618_034;
+/// The ratio between the inradius and circumradius of both dodecahedrons and icosahedrons
+const ICOSAHEDRAL_INRADIUS: f32 = 0.794_654_5;
+const SQRT_3: f32 = 1.732_050_8;
+const FRAC_1_SQRT_3: f32 = 0.577_350_3;
+
+/// A user defined primitive, for shapes that aren't covered by [`SDFPrimitive`]
+///
+/// Custom primitives are compared by identity, so two elements are only equal
+/// if they share the same instance.
+pub trait CustomPrimitive: Send + Sync {
+    /// Get the value of the SDF at a given point
+    ///
+    /// The value shouldn't overestimate the distance to the surface
+    fn distance(&self, point: Vec3) -> f32;
+
+    /// Get the bounds of the SDF
+    ///
+    /// Unbounded primitives should use infinite values along the unbounded axes
+    fn bounds(&self) -> (Vec3, Vec3);
+}
+
+impl PartialEq for dyn CustomPrimitive {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            self as *const dyn CustomPrimitive as *const u8,
+            other as *const dyn CustomPrimitive as *const u8,
+        )
+    }
+}
+
+impl Debug for dyn CustomPrimitive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomPrimitive")
+            .field("bounds", &self.bounds())
+            .finish()
+    }
+}
 
 /// The basic primitives comprising an Signed Distance Field
 #[derive(Debug, Clone, PartialEq)]
@@ -9,6 +63,168 @@ pub enum SDFPrimitive {
     Sphere(f32),
     /// Defines a box, provided it's half bounds
     Box(Vec3),
+    /// Defines an ellipsoid, provided it's radius along each axis
+    ///
+    /// The distance is a conservative bound, and is only exact along the shortest axis
+    Ellipsoid(Vec3),
+    /// Defines a box with rounded edges
+    RoundedBox {
+        /// The half bounds of the box, including the rounded edges
+        half_extents: Vec3,
+        /// The radius of the rounded edges
+        radius: f32,
+    },
+    /// Defines a torus lying in the XZ plane
+    Torus {
+        /// The distance from the center to the middle of the tube
+        major: f32,
+        /// The radius of the tube
+        minor: f32,
+    },
+    /// Defines a section of a torus lying in the XZ plane, centered around the Z axis
+    CappedTorus {
+        /// The distance from the center to the middle of the tube
+        major: f32,
+        /// The radius of the tube
+        minor: f32,
+        /// Half of the arc covered by the torus, in radians
+        angle: f32,
+    },
+    /// Defines a capsule around the line segment between two points
+    Capsule {
+        /// The start of the segment
+        a: Vec3,
+        /// The end of the segment
+        b: Vec3,
+        /// The radius of the capsule
+        radius: f32,
+    },
+    /// Defines a capsule centered on the origin, along the Y axis
+    VerticalCapsule {
+        /// Half the length of the central segment
+        half_height: f32,
+        /// The radius of the capsule
+        radius: f32,
+    },
+    /// Defines a cylinder centered on the origin, along the Y axis
+    CappedCylinder {
+        /// Half the height of the cylinder
+        half_height: f32,
+        /// The radius of the cylinder
+        radius: f32,
+    },
+    /// Defines an infinitely long cylinder along the Y axis, provided it's radius
+    ///
+    /// The bounds are infinite along the Y axis
+    InfiniteCylinder(f32),
+    /// Defines a cone with a flat top and bottom, centered on the origin along the Y axis
+    ///
+    /// A pointed cone can be made by setting one of the radii to 0
+    CappedCone {
+        /// Half the height of the cone
+        half_height: f32,
+        /// The radius at the bottom of the cone
+        bottom_radius: f32,
+        /// The radius at the top of the cone
+        top_radius: f32,
+    },
+    /// Defines a cone with spherical ends, centered on the origin along the Y axis
+    ///
+    /// The distance between the sphere centers should be larger than the difference between their radii.
+    /// A height of 0 gives a sphere with the larger radius
+    RoundCone {
+        /// The distance between the centers of the two spheres
+        height: f32,
+        /// The radius of the bottom sphere
+        bottom_radius: f32,
+        /// The radius of the top sphere
+        top_radius: f32,
+    },
+    /// Defines a half-space below a plane, provided the plane's normal and it's distance from the origin
+    ///
+    /// The bounds are infinite, unless the normal is aligned with an axis
+    Plane {
+        /// The normal of the plane, pointing away from the filled half-space
+        normal: Vec3,
+        /// The distance of the plane from the origin, along the normal
+        offset: f32,
+    },
+    /// Defines a 2D primitive on the XY plane, extruded along the Z axis
+    Extrusion {
+        /// The shape being extruded
+        shape: SDFPrimitive2D,
+        /// Half the depth of the extrusion
+        half_depth: f32,
+    },
+    /// Defines a profile of 2D primitives revolved around the Y axis
+    ///
+    /// The profile's X axis is the distance from the Y axis, so it should lie on the positive side,
+    /// and any shapes crossing the axis should be symmetric around it.
+    /// A polyline profile can be provided as a `Polygon`, closing back along the Y axis.
+    Revolution(Vec<SDFPrimitive2D>),
+    /// Defines a triangle mesh, see [`SDFMesh`]
+    Mesh(Arc<SDFMesh>),
+    /// Defines a terrain height field, see [`SDFHeightfield`]
+    Heightfield(Arc<SDFHeightfield>),
+    /// Defines a grid of pre-calculated distances, see [`SDFGrid`]
+    Grid(Arc<SDFGrid>),
+    /// Defines a tube following a spline, see [`SDFSpline`]
+    Spline(Arc<SDFSpline>),
+    /// Defines extruded text, see [`SDFText`]
+    Text(Arc<SDFText>),
+    /// Defines a convex polyhedron from the planes of it's faces, see [`SDFConvexPolyhedron`]
+    ConvexPolyhedron(Arc<SDFConvexPolyhedron>),
+    /// Defines a user defined primitive, see [`CustomPrimitive`]
+    Custom(Arc<dyn CustomPrimitive>),
+    /// Defines the section of a sphere within a cone, with it's tip at the origin opening along the Y axis
+    SolidAngle {
+        /// The angle between the Y axis and the side of the cone, in radians
+        angle: f32,
+        /// The radius of the sphere
+        radius: f32,
+    },
+    /// Defines a regular octahedron, provided the distance from it's center to it's vertices
+    ///
+    /// The vertices lie on the axes
+    Octahedron(f32),
+    /// Defines a regular tetrahedron, provided the distance from it's center to it's vertices
+    ///
+    /// The vertices lie on alternating corners of a cube, with one at `(1, 1, 1)`.
+    /// The distance is exact near the faces, and a conservative bound near the edges.
+    Tetrahedron(f32),
+    /// Defines a regular dodecahedron, provided the distance from it's center to it's vertices
+    ///
+    /// The distance is exact near the faces, and a conservative bound near the edges.
+    Dodecahedron(f32),
+    /// Defines a regular icosahedron, provided the distance from it's center to it's vertices
+    ///
+    /// The distance is exact near the faces, and a conservative bound near the edges.
+    Icosahedron(f32),
+    /// Defines a prism with a regular hexagon for a base, centered on the origin along the Y axis
+    ///
+    /// The flat sides of the hexagon face the Z axis
+    HexagonalPrism {
+        /// The distance from the center of the hexagon to the middle of it's sides
+        radius: f32,
+        /// Half the height of the prism
+        half_height: f32,
+    },
+    /// Defines a prism with an equilateral triangle for a base, centered on the origin along the Y axis
+    ///
+    /// One corner of the triangle points along the Z axis
+    TriangularPrism {
+        /// The distance from the center of the triangle to the middle of it's sides
+        radius: f32,
+        /// Half the height of the prism
+        half_height: f32,
+    },
+    /// Defines a pyramid with a square base, centered on the origin and pointing along the Y axis
+    SquarePyramid {
+        /// Half the length of the sides of the base
+        half_base: f32,
+        /// The height from the base to the tip
+        height: f32,
+    },
 }
 
 impl SDFPrimitive {
@@ -18,14 +234,215 @@ impl SDFPrimitive {
         match self {
             SDFPrimitive::Sphere(radius) => sphere_sdf(point, *radius),
             SDFPrimitive::Box(bounds) => box_sdf(point, *bounds),
+            SDFPrimitive::Ellipsoid(radii) => ellipsoid_sdf(point, *radii),
+            SDFPrimitive::RoundedBox {
+                half_extents,
+                radius,
+            } => rounded_box_sdf(point, *half_extents, *radius),
+            SDFPrimitive::Torus { major, minor } => torus_sdf(point, *major, *minor),
+            SDFPrimitive::CappedTorus {
+                major,
+                minor,
+                angle,
+            } => capped_torus_sdf(point, *major, *minor, *angle),
+            SDFPrimitive::Capsule { a, b, radius } => capsule_sdf(point, *a, *b, *radius),
+            SDFPrimitive::VerticalCapsule {
+                half_height,
+                radius,
+            } => vertical_capsule_sdf(point, *half_height, *radius),
+            SDFPrimitive::CappedCylinder {
+                half_height,
+                radius,
+            } => capped_cylinder_sdf(point, *half_height, *radius),
+            SDFPrimitive::InfiniteCylinder(radius) => point.xz().length() - radius,
+            SDFPrimitive::CappedCone {
+                half_height,
+                bottom_radius,
+                top_radius,
+            } => capped_cone_sdf(point, *half_height, *bottom_radius, *top_radius),
+            SDFPrimitive::RoundCone {
+                height,
+                bottom_radius,
+                top_radius,
+            } => round_cone_sdf(point, *height, *bottom_radius, *top_radius),
+            SDFPrimitive::SolidAngle { angle, radius } => solid_angle_sdf(point, *angle, *radius),
+            SDFPrimitive::Octahedron(radius) => octahedron_sdf(point, *radius),
+            SDFPrimitive::Tetrahedron(radius) => tetrahedron_sdf(point, *radius),
+            SDFPrimitive::Dodecahedron(radius) => dodecahedron_sdf(point, *radius),
+            SDFPrimitive::Icosahedron(radius) => icosahedron_sdf(point, *radius),
+            SDFPrimitive::HexagonalPrism {
+                radius,
+                half_height,
+            } => hexagonal_prism_sdf(point, *radius, *half_height),
+            SDFPrimitive::TriangularPrism {
+                radius,
+                half_height,
+            } => triangular_prism_sdf(point, *radius, *half_height),
+            SDFPrimitive::SquarePyramid { half_base, height } => {
+                square_pyramid_sdf(point, *half_base, *height)
+            }
+            SDFPrimitive::Plane { normal, offset } => plane_sdf(point, *normal, *offset),
+            SDFPrimitive::Extrusion { shape, half_depth } => {
+                extrusion_sdf(point, shape, *half_depth)
+            }
+            SDFPrimitive::Revolution(profile) => revolution_sdf(point, profile),
+            SDFPrimitive::Mesh(mesh) => mesh.value_at_point(point),
+            SDFPrimitive::Heightfield(heightfield) => heightfield.value_at_point(point),
+            SDFPrimitive::Grid(grid) => grid.value_at_point(point),
+            SDFPrimitive::Spline(spline) => spline.value_at_point(point),
+            SDFPrimitive::Text(text) => text.value_at_point(point),
+            SDFPrimitive::ConvexPolyhedron(polyhedron) => polyhedron.value_at_point(point),
+            SDFPrimitive::Custom(custom) => custom.distance(point),
         }
     }
 
     /// Get the bounds of the SDF
+    ///
+    /// Unbounded primitives use infinite values along the unbounded axes
     pub fn get_bounds(&self) -> (Vec3, Vec3) {
         match self {
             SDFPrimitive::Sphere(radius) => (-1. * Vec3::ONE * *radius, Vec3::ONE * *radius),
             SDFPrimitive::Box(bounds) => (-1. * *bounds, *bounds),
+            SDFPrimitive::Ellipsoid(radii) => (-1. * *radii, *radii),
+            SDFPrimitive::RoundedBox { half_extents, .. } => (-1. * *half_extents, *half_extents),
+            SDFPrimitive::Torus { major, minor } => {
+                let bounds = Vec3::new(major + minor, *minor, major + minor);
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::CappedTorus {
+                major,
+                minor,
+                angle,
+            } => {
+                let angle = angle.clamp(0., PI);
+                let x = if angle < FRAC_PI_2 {
+                    major * angle.sin()
+                } else {
+                    *major
+                };
+                (
+                    Vec3::new(-x - minor, -minor, major * angle.cos() - minor),
+                    Vec3::new(x + minor, *minor, major + minor),
+                )
+            }
+            SDFPrimitive::Capsule { a, b, radius } => (a.min(*b) - *radius, a.max(*b) + *radius),
+            SDFPrimitive::VerticalCapsule {
+                half_height,
+                radius,
+            } => {
+                let bounds = Vec3::new(*radius, half_height + radius, *radius);
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::CappedCylinder {
+                half_height,
+                radius,
+            } => {
+                let bounds = Vec3::new(*radius, *half_height, *radius);
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::InfiniteCylinder(radius) => {
+                let bounds = Vec3::new(*radius, f32::INFINITY, *radius);
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::CappedCone {
+                half_height,
+                bottom_radius,
+                top_radius,
+            } => {
+                let radius = bottom_radius.max(*top_radius);
+                let bounds = Vec3::new(radius, *half_height, radius);
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::RoundCone {
+                height,
+                bottom_radius,
+                top_radius,
+            } => {
+                let radius = bottom_radius.max(*top_radius);
+                let half_height = height / 2.;
+                (
+                    Vec3::new(
+                        -radius,
+                        (-half_height - bottom_radius).min(half_height - top_radius),
+                        -radius,
+                    ),
+                    Vec3::new(
+                        radius,
+                        (half_height + top_radius).max(-half_height + bottom_radius),
+                        radius,
+                    ),
+                )
+            }
+            SDFPrimitive::SolidAngle { angle, radius } => {
+                let angle = angle.clamp(0., PI);
+                let horizontal = if angle < FRAC_PI_2 {
+                    radius * angle.sin()
+                } else {
+                    *radius
+                };
+                (
+                    Vec3::new(-horizontal, (radius * angle.cos()).min(0.), -horizontal),
+                    Vec3::new(horizontal, *radius, horizontal),
+                )
+            }
+            SDFPrimitive::Octahedron(radius) => (Vec3::splat(-radius), Vec3::splat(*radius)),
+            SDFPrimitive::Tetrahedron(radius) => {
+                let bounds = Vec3::splat(radius * FRAC_1_SQRT_3);
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::Dodecahedron(radius) => {
+                // The furthest vertices along each axis are `(0, 1 / phi, phi)` and it's rotations
+                let bounds = Vec3::splat(radius * GOLDEN_RATIO * FRAC_1_SQRT_3);
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::Icosahedron(radius) => {
+                // The furthest vertices along each axis are `(0, 1, phi)` and it's rotations
+                let bounds =
+                    Vec3::splat(radius * GOLDEN_RATIO / (1. + GOLDEN_RATIO * GOLDEN_RATIO).sqrt());
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::HexagonalPrism {
+                radius,
+                half_height,
+            } => {
+                let bounds = Vec3::new(2. * radius * FRAC_1_SQRT_3, *half_height, *radius);
+                (-1. * bounds, bounds)
+            }
+            SDFPrimitive::TriangularPrism {
+                radius,
+                half_height,
+            } => (
+                Vec3::new(-radius * SQRT_3, -half_height, -radius),
+                Vec3::new(radius * SQRT_3, *half_height, 2. * radius),
+            ),
+            SDFPrimitive::SquarePyramid { half_base, height } => (
+                Vec3::new(-half_base, -height / 2., -half_base),
+                Vec3::new(*half_base, height / 2., *half_base),
+            ),
+            SDFPrimitive::Plane { normal, offset } => plane_bounds(*normal, *offset),
+            SDFPrimitive::Extrusion { shape, half_depth } => {
+                let bounds = shape.get_bounds();
+                (bounds.0.extend(-half_depth), bounds.1.extend(*half_depth))
+            }
+            SDFPrimitive::Revolution(profile) => {
+                let (min, max) = profile
+                    .iter()
+                    .map(|shape| shape.get_bounds())
+                    .reduce(|a, b| (a.0.min(b.0), a.1.max(b.1)))
+                    .unwrap_or((Vec2::ZERO, Vec2::ZERO));
+                let radius = min.x.abs().max(max.x.abs());
+                (
+                    Vec3::new(-radius, min.y, -radius),
+                    Vec3::new(radius, max.y, radius),
+                )
+            }
+            SDFPrimitive::Mesh(mesh) => mesh.get_bounds(),
+            SDFPrimitive::Heightfield(heightfield) => heightfield.get_bounds(),
+            SDFPrimitive::Grid(grid) => grid.get_bounds(),
+            SDFPrimitive::Spline(spline) => spline.get_bounds(),
+            SDFPrimitive::Text(text) => text.get_bounds(),
+            SDFPrimitive::ConvexPolyhedron(polyhedron) => polyhedron.get_bounds(),
+            SDFPrimitive::Custom(custom) => custom.bounds(),
         }
     }
 }
@@ -39,11 +456,258 @@ fn box_sdf(point: Vec3, bounds: Vec3) -> f32 {
     q.max(Vec3::ZERO).length() + q.y.max(q.z).max(q.x).min(0.)
 }
 
+fn ellipsoid_sdf(point: Vec3, radii: Vec3) -> f32 {
+    // The length of the point in the unit sphere's space changes by at most 1 / min radius
+    // per unit moved, so scaling by the min radius gives a bound that never overestimates
+    ((point / radii).length() - 1.) * radii.min_element()
+}
+
+fn rounded_box_sdf(point: Vec3, half_extents: Vec3, radius: f32) -> f32 {
+    let radius = radius.clamp(0., half_extents.min_element());
+    box_sdf(point, half_extents - radius) - radius
+}
+
+fn torus_sdf(point: Vec3, major: f32, minor: f32) -> f32 {
+    let q = Vec2::new(point.xz().length() - major, point.y);
+    q.length() - minor
+}
+
+fn capped_torus_sdf(point: Vec3, major: f32, minor: f32, angle: f32) -> f32 {
+    let angle = angle.clamp(0., PI);
+    let cap = Vec2::new(angle.sin(), angle.cos());
+    let arc = Vec2::new(point.x.abs(), point.z);
+    let k = if cap.y * arc.x > cap.x * arc.y {
+        arc.dot(cap)
+    } else {
+        arc.length()
+    };
+    (point.length_squared() + major * major - 2. * major * k)
+        .max(0.)
+        .sqrt()
+        - minor
+}
+
+fn capsule_sdf(point: Vec3, a: Vec3, b: Vec3, radius: f32) -> f32 {
+    let pa = point - a;
+    let ba = b - a;
+    let length_squared = ba.length_squared();
+    let h = if length_squared > 0. {
+        (pa.dot(ba) / length_squared).clamp(0., 1.)
+    } else {
+        0.
+    };
+    (pa - ba * h).length() - radius
+}
+
+fn vertical_capsule_sdf(point: Vec3, half_height: f32, radius: f32) -> f32 {
+    let mut point = point;
+    point.y -= point.y.clamp(-half_height, half_height);
+    point.length() - radius
+}
+
+fn capped_cylinder_sdf(point: Vec3, half_height: f32, radius: f32) -> f32 {
+    let d = Vec2::new(point.xz().length(), point.y).abs() - Vec2::new(radius, half_height);
+    d.x.max(d.y).min(0.) + d.max(Vec2::ZERO).length()
+}
+
+fn capped_cone_sdf(point: Vec3, half_height: f32, bottom_radius: f32, top_radius: f32) -> f32 {
+    let q = Vec2::new(point.xz().length(), point.y);
+    let k1 = Vec2::new(top_radius, half_height);
+    let k2 = Vec2::new(top_radius - bottom_radius, 2. * half_height);
+    let ca = Vec2::new(
+        q.x - q.x.min(if q.y < 0. { bottom_radius } else { top_radius }),
+        q.y.abs() - half_height,
+    );
+    let cb = q - k1 + k2 * ((k1 - q).dot(k2) / k2.length_squared()).clamp(0., 1.);
+    let sign = if cb.x < 0. && ca.y < 0. { -1. } else { 1. };
+    sign * ca.length_squared().min(cb.length_squared()).sqrt()
+}
+
+fn round_cone_sdf(point: Vec3, height: f32, bottom_radius: f32, top_radius: f32) -> f32 {
+    // With both spheres at the same center, the larger one contains the smaller one
+    if height <= 0. {
+        return point.length() - bottom_radius.max(top_radius);
+    }
+    let b = (bottom_radius - top_radius) / height;
+    let a = (1. - b * b).max(0.).sqrt();
+    let q = Vec2::new(point.xz().length(), point.y + height / 2.);
+    let k = q.dot(Vec2::new(-b, a));
+    if k < 0. {
+        q.length() - bottom_radius
+    } else if k > a * height {
+        (q - Vec2::new(0., height)).length() - top_radius
+    } else {
+        q.dot(Vec2::new(a, b)) - bottom_radius
+    }
+}
+
+fn solid_angle_sdf(point: Vec3, angle: f32, radius: f32) -> f32 {
+    let angle = angle.clamp(0., PI);
+    let c = Vec2::new(angle.sin(), angle.cos());
+    let q = Vec2::new(point.xz().length(), point.y);
+    let l = q.length() - radius;
+    let m = (q - c * q.dot(c).clamp(0., radius)).length();
+    l.max(m * (c.y * q.x - c.x * q.y).signum())
+}
+
+fn octahedron_sdf(point: Vec3, radius: f32) -> f32 {
+    let point = point.abs();
+    let m = point.x + point.y + point.z - radius;
+    let q = if 3. * point.x < m {
+        point
+    } else if 3. * point.y < m {
+        point.yzx()
+    } else if 3. * point.z < m {
+        point.zxy()
+    } else {
+        return m * FRAC_1_SQRT_3;
+    };
+    let k = (0.5 * (q.z - q.y + radius)).clamp(0., radius);
+    Vec3::new(q.x, q.y - radius + k, q.z - k).length()
+}
+
+/// Get the distance from the furthest face plane of a solid, provided the face normals
+fn face_planes_sdf(point: Vec3, normals: &[Vec3], inradius: f32) -> f32 {
+    normals
+        .iter()
+        .map(|normal| point.dot(*normal))
+        .fold(f32::NEG_INFINITY, f32::max)
+        - inradius
+}
+
+fn tetrahedron_sdf(point: Vec3, radius: f32) -> f32 {
+    let normals = [
+        Vec3::new(1., 1., -1.),
+        Vec3::new(1., -1., 1.),
+        Vec3::new(-1., 1., 1.),
+        Vec3::new(-1., -1., -1.),
+    ]
+    .map(|normal| normal * FRAC_1_SQRT_3);
+    face_planes_sdf(point, &normals, radius / 3.)
+}
+
+fn dodecahedron_sdf(point: Vec3, radius: f32) -> f32 {
+    // Both solids are symmetric across each axis, so only the faces facing
+    // the positive octant need to be checked
+    let normals = [
+        Vec3::new(1., 0., GOLDEN_RATIO),
+        Vec3::new(GOLDEN_RATIO, 1., 0.),
+        Vec3::new(0., GOLDEN_RATIO, 1.),
+    ]
+    .map(|normal| normal.normalize());
+    face_planes_sdf(point.abs(), &normals, radius * ICOSAHEDRAL_INRADIUS)
+}
+
+fn icosahedron_sdf(point: Vec3, radius: f32) -> f32 {
+    let inverse = 1. / GOLDEN_RATIO;
+    let normals = [
+        Vec3::ONE,
+        Vec3::new(inverse, 0., GOLDEN_RATIO),
+        Vec3::new(GOLDEN_RATIO, inverse, 0.),
+        Vec3::new(0., GOLDEN_RATIO, inverse),
+    ]
+    .map(|normal| normal * FRAC_1_SQRT_3);
+    face_planes_sdf(point.abs(), &normals, radius * ICOSAHEDRAL_INRADIUS)
+}
+
+/// Extrude a 2D distance along an axis, provided the position along that axis
+fn extrude(distance: f32, position: f32, half_depth: f32) -> f32 {
+    let w = Vec2::new(distance, position.abs() - half_depth);
+    w.x.max(w.y).min(0.) + w.max(Vec2::ZERO).length()
+}
+
+fn hexagonal_prism_sdf(point: Vec3, radius: f32, half_height: f32) -> f32 {
+    let k = Vec2::new(-SQRT_3 / 2., 0.5);
+    let mut q = point.xz().abs();
+    q -= 2. * k.dot(q).min(0.) * k;
+    let corner = radius * FRAC_1_SQRT_3;
+    let edge = (q - Vec2::new(q.x.clamp(-corner, corner), radius)).length();
+    let hexagon = if q.y < radius { -edge } else { edge };
+    extrude(hexagon, point.y, half_height)
+}
+
+fn triangular_prism_sdf(point: Vec3, radius: f32, half_height: f32) -> f32 {
+    let half_side = radius * SQRT_3;
+    let mut q = Vec2::new(point.x.abs() - half_side, point.z + radius);
+    if q.x + SQRT_3 * q.y > 0. {
+        q = Vec2::new(q.x - SQRT_3 * q.y, -SQRT_3 * q.x - q.y) / 2.;
+    }
+    q.x -= q.x.clamp(-2. * half_side, 0.);
+    let triangle = if q.y > 0. { -q.length() } else { q.length() };
+    extrude(triangle, point.y, half_height)
+}
+
+fn square_pyramid_sdf(point: Vec3, half_base: f32, height: f32) -> f32 {
+    // Work on a pyramid with a unit base, sitting on the XZ plane
+    let scale = 2. * half_base;
+    let h = height / scale;
+    let point = (point + Vec3::Y * height / 2.) / scale;
+    if point.y < 0. {
+        // Below the base, the closest point is always on the base
+        let outside = (point.xz().abs() - 0.5).max(Vec2::ZERO);
+        return outside.extend(point.y).length() * scale;
+    }
+    let m2 = h * h + 0.25;
+
+    let mut xz = point.xz().abs();
+    if xz.y > xz.x {
+        xz = xz.yx();
+    }
+    xz -= 0.5;
+    let q = Vec3::new(xz.y, h * point.y - 0.5 * xz.x, h * xz.x + 0.5 * point.y);
+    let s = (-q.x).max(0.);
+    let t = ((q.y - 0.5 * xz.y) / (m2 + 0.25)).clamp(0., 1.);
+    let a = m2 * (q.x + s) * (q.x + s) + q.y * q.y;
+    let b = m2 * (q.x + 0.5 * t) * (q.x + 0.5 * t) + (q.y - m2 * t) * (q.y - m2 * t);
+    let d2 = if q.y.min(-q.x * m2 - q.y * 0.5) > 0. {
+        0.
+    } else {
+        a.min(b)
+    };
+    let sides = ((d2 + q.z * q.z) / m2).sqrt() * q.z.max(-point.y).signum();
+    // The sides alone don't account for the base from the inside
+    sides.max(-point.y) * scale
+}
+
+fn plane_sdf(point: Vec3, normal: Vec3, offset: f32) -> f32 {
+    point.dot(normal.normalize_or_zero()) - offset
+}
+
+fn plane_bounds(normal: Vec3, offset: f32) -> (Vec3, Vec3) {
+    let normal = normal.normalize_or_zero();
+    let mut bounds = (Vec3::splat(f32::NEG_INFINITY), Vec3::splat(f32::INFINITY));
+    for axis in 0..3 {
+        if (normal[axis].abs() - 1.).abs() < 1e-6 {
+            if normal[axis] > 0. {
+                bounds.1[axis] = offset;
+            } else {
+                bounds.0[axis] = -offset;
+            }
+        }
+    }
+    bounds
+}
+
+fn extrusion_sdf(point: Vec3, shape: &SDFPrimitive2D, half_depth: f32) -> f32 {
+    extrude(shape.value_at_point(&point.xy()), point.z, half_depth)
+}
+
+fn revolution_sdf(point: Vec3, profile: &[SDFPrimitive2D]) -> f32 {
+    let q = Vec2::new(point.xz().length(), point.y);
+    profile
+        .iter()
+        .map(|shape| shape.revolved_value_at_point(&q))
+        .fold(f32::INFINITY, f32::min)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::f32::consts::FRAC_1_SQRT_2;
+
     use assert_float_eq::*;
 
     use super::*;
+    use crate::utils::sample_points;
 
     #[test]
     fn calculates_sphere_sdf() {
@@ -94,4 +758,796 @@ mod tests {
         assert_float_absolute_eq!(bounds.1.y, 1.);
         assert_float_absolute_eq!(bounds.1.z, 2.);
     }
+
+    #[test]
+    fn calculates_ellipsoid_sdf() {
+        let sdf = SDFPrimitive::Ellipsoid(Vec3::new(1., 2., 3.));
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let surface = sdf.value_at_point(&(Vec3::Z * 3.));
+        let outside = sdf.value_at_point(&Vec3::new(1.5, 0., 0.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn ellipsoid_sdf_is_lipschitz_bound() {
+        let sdf = SDFPrimitive::Ellipsoid(Vec3::new(0.5, 2., 3.));
+
+        for a in sample_points(9, Vec3::splat(0.9), Vec3::splat(-3.6)) {
+            for b in sample_points(9, Vec3::splat(0.9), Vec3::splat(-3.6)).step_by(7) {
+                let difference = (sdf.value_at_point(&a) - sdf.value_at_point(&b)).abs();
+                assert!(difference <= a.distance(b) + 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn ellipsoid_sdf_never_overestimates() {
+        let radii = Vec3::new(0.5, 2., 3.);
+        let sdf = SDFPrimitive::Ellipsoid(radii);
+        let surface = (0..64)
+            .flat_map(|i| {
+                (0..=32).map(move |j| {
+                    let theta = i as f32 / 64. * 2. * PI;
+                    let phi = j as f32 / 32. * PI;
+                    Vec3::new(theta.cos() * phi.sin(), phi.cos(), theta.sin() * phi.sin()) * radii
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for point in sample_points(9, Vec3::splat(0.9), Vec3::splat(-3.6)) {
+            let closest = surface
+                .iter()
+                .map(|s| s.distance(point))
+                .fold(f32::INFINITY, f32::min);
+            assert!(sdf.value_at_point(&point).abs() <= closest + 1e-4);
+        }
+    }
+
+    #[test]
+    fn calculates_rounded_box_sdf() {
+        let sdf = SDFPrimitive::RoundedBox {
+            half_extents: Vec3::new(1., 2., 1.),
+            radius: 0.5,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let surface = sdf.value_at_point(&(Vec3::Y * 2.));
+        let edge = sdf.value_at_point(&Vec3::new(1., 2., 0.));
+        let outside = sdf.value_at_point(&Vec3::new(1.5, 0., 0.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(edge, 2f32.sqrt() * 0.5 - 0.5);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculates_torus_sdf() {
+        let sdf = SDFPrimitive::Torus {
+            major: 2.,
+            minor: 0.5,
+        };
+
+        let interior = sdf.value_at_point(&(Vec3::X * 2.));
+        let surface = sdf.value_at_point(&Vec3::new(0., 0.5, 2.));
+        let hole = sdf.value_at_point(&Vec3::ZERO);
+        let outside = sdf.value_at_point(&Vec3::new(-3., 0., 0.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(hole, 1.5);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculates_capped_torus_sdf() {
+        let sdf = SDFPrimitive::CappedTorus {
+            major: 2.,
+            minor: 0.5,
+            angle: FRAC_PI_2,
+        };
+
+        let interior = sdf.value_at_point(&(Vec3::Z * 2.));
+        let surface = sdf.value_at_point(&Vec3::new(0., 0.5, 2.));
+        let cap = sdf.value_at_point(&Vec3::new(2., 0., -0.5));
+        let outside = sdf.value_at_point(&(Vec3::Z * -2.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(cap, 0.);
+        assert_float_absolute_eq!(outside, 8f32.sqrt() - 0.5);
+    }
+
+    #[test]
+    fn calculate_ellipsoid_bounds() {
+        let sdf = SDFPrimitive::Ellipsoid(Vec3::new(1.5, 1., 2.));
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.5);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -2.);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_float_absolute_eq!(bounds.1.z, 2.);
+    }
+
+    #[test]
+    fn calculate_rounded_box_bounds() {
+        let sdf = SDFPrimitive::RoundedBox {
+            half_extents: Vec3::new(1.5, 1., 2.),
+            radius: 0.5,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.5);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -2.);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_float_absolute_eq!(bounds.1.z, 2.);
+    }
+
+    #[test]
+    fn calculate_torus_bounds() {
+        let sdf = SDFPrimitive::Torus {
+            major: 2.,
+            minor: 0.5,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -2.5);
+        assert_float_absolute_eq!(bounds.0.y, -0.5);
+        assert_float_absolute_eq!(bounds.0.z, -2.5);
+        assert_float_absolute_eq!(bounds.1.x, 2.5);
+        assert_float_absolute_eq!(bounds.1.y, 0.5);
+        assert_float_absolute_eq!(bounds.1.z, 2.5);
+    }
+
+    #[test]
+    fn calculate_capped_torus_bounds() {
+        let sdf = SDFPrimitive::CappedTorus {
+            major: 2.,
+            minor: 0.5,
+            angle: FRAC_PI_2 / 3.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.5);
+        assert_float_absolute_eq!(bounds.0.y, -0.5);
+        assert_float_absolute_eq!(bounds.0.z, 3f32.sqrt() - 0.5);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, 0.5);
+        assert_float_absolute_eq!(bounds.1.z, 2.5);
+    }
+
+    #[test]
+    fn calculates_capsule_sdf() {
+        let sdf = SDFPrimitive::Capsule {
+            a: Vec3::ZERO,
+            b: Vec3::X * 2.,
+            radius: 0.5,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::X);
+        let surface = sdf.value_at_point(&Vec3::new(1., 0.5, 0.));
+        let cap = sdf.value_at_point(&Vec3::new(2.5, 0., 0.));
+        let outside = sdf.value_at_point(&Vec3::new(-1., 0., 0.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(cap, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculates_vertical_capsule_sdf() {
+        let sdf = SDFPrimitive::VerticalCapsule {
+            half_height: 1.,
+            radius: 0.5,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let surface = sdf.value_at_point(&(Vec3::Y * 1.5));
+        let side = sdf.value_at_point(&Vec3::new(1., 0.5, 0.));
+        let outside = sdf.value_at_point(&(Vec3::Y * -2.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(side, 0.5);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculate_capsule_bounds() {
+        let sdf = SDFPrimitive::Capsule {
+            a: Vec3::new(1., -1., 0.),
+            b: Vec3::new(-1., 2., 0.),
+            radius: 0.5,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.5);
+        assert_float_absolute_eq!(bounds.0.y, -1.5);
+        assert_float_absolute_eq!(bounds.0.z, -0.5);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, 2.5);
+        assert_float_absolute_eq!(bounds.1.z, 0.5);
+    }
+
+    #[test]
+    fn calculate_vertical_capsule_bounds() {
+        let sdf = SDFPrimitive::VerticalCapsule {
+            half_height: 1.,
+            radius: 0.5,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -0.5);
+        assert_float_absolute_eq!(bounds.0.y, -1.5);
+        assert_float_absolute_eq!(bounds.0.z, -0.5);
+        assert_float_absolute_eq!(bounds.1.x, 0.5);
+        assert_float_absolute_eq!(bounds.1.y, 1.5);
+        assert_float_absolute_eq!(bounds.1.z, 0.5);
+    }
+
+    #[test]
+    fn calculates_capped_cylinder_sdf() {
+        let sdf = SDFPrimitive::CappedCylinder {
+            half_height: 2.,
+            radius: 1.,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let surface = sdf.value_at_point(&Vec3::new(0., 2., 0.5));
+        let side = sdf.value_at_point(&Vec3::new(0., 1., 1.5));
+        let corner = sdf.value_at_point(&Vec3::new(4., 6., 0.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(side, 0.5);
+        assert_float_absolute_eq!(corner, 5.);
+    }
+
+    #[test]
+    fn calculates_infinite_cylinder_sdf() {
+        let sdf = SDFPrimitive::InfiniteCylinder(1.);
+
+        let interior = sdf.value_at_point(&(Vec3::Y * 100.));
+        let surface = sdf.value_at_point(&Vec3::new(0., -50., 1.));
+        let outside = sdf.value_at_point(&Vec3::new(1.5, 10., 0.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculates_capped_cone_sdf() {
+        let sdf = SDFPrimitive::CappedCone {
+            half_height: 1.,
+            bottom_radius: 2.,
+            top_radius: 1.,
+        };
+
+        let interior = sdf.value_at_point(&(Vec3::Y * 0.5));
+        let top = sdf.value_at_point(&(Vec3::Y * 1.5));
+        let bottom = sdf.value_at_point(&Vec3::new(1., -1., 0.));
+        let side = sdf.value_at_point(&Vec3::new(1.5, 0., 0.));
+        let outside = sdf.value_at_point(&Vec3::new(0., -1., 3.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(top, 0.5);
+        assert_float_absolute_eq!(bottom, 0.);
+        assert_float_absolute_eq!(side, 0.);
+        assert_float_absolute_eq!(outside, 1.);
+    }
+
+    #[test]
+    fn calculates_round_cone_sdf() {
+        let sdf = SDFPrimitive::RoundCone {
+            height: 2.,
+            bottom_radius: 1.,
+            top_radius: 0.5,
+        };
+
+        let interior = sdf.value_at_point(&(Vec3::Y * -1.));
+        let bottom = sdf.value_at_point(&(Vec3::Y * -2.5));
+        let top = sdf.value_at_point(&(Vec3::Y * 1.5));
+        let side = sdf.value_at_point(&Vec3::new(1., -1., 0.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(bottom, 0.5);
+        assert_float_absolute_eq!(top, 0.);
+        assert_float_absolute_eq!(side, 0.);
+    }
+
+    #[test]
+    fn flat_round_cone_is_a_sphere() {
+        let sdf = SDFPrimitive::RoundCone {
+            height: 0.,
+            bottom_radius: 1.,
+            top_radius: 1.5,
+        };
+
+        assert_float_absolute_eq!(sdf.value_at_point(&(Vec3::Y * 2.)), 0.5);
+        assert_float_absolute_eq!(sdf.value_at_point(&Vec3::ZERO), -1.5);
+    }
+
+    #[test]
+    fn calculates_solid_angle_sdf() {
+        let sdf = SDFPrimitive::SolidAngle {
+            angle: FRAC_PI_2 / 2.,
+            radius: 2.,
+        };
+
+        let interior = sdf.value_at_point(&(Vec3::Y * 1.5));
+        let surface = sdf.value_at_point(&(Vec3::Y * 2.));
+        let side = sdf.value_at_point(&Vec3::new(1., 1., 0.));
+        let below = sdf.value_at_point(&(Vec3::Y * -1.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(side, 0.);
+        assert_float_absolute_eq!(below, 1.);
+    }
+
+    #[test]
+    fn calculate_capped_cylinder_bounds() {
+        let sdf = SDFPrimitive::CappedCylinder {
+            half_height: 2.,
+            radius: 1.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, -2.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn calculate_infinite_cylinder_bounds() {
+        let sdf = SDFPrimitive::InfiniteCylinder(1.);
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_eq!(bounds.0.y, f32::NEG_INFINITY);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_eq!(bounds.1.y, f32::INFINITY);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn calculate_capped_cone_bounds() {
+        let sdf = SDFPrimitive::CappedCone {
+            half_height: 1.,
+            bottom_radius: 2.,
+            top_radius: 1.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -2.);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -2.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_float_absolute_eq!(bounds.1.z, 2.);
+    }
+
+    #[test]
+    fn calculate_round_cone_bounds() {
+        let sdf = SDFPrimitive::RoundCone {
+            height: 2.,
+            bottom_radius: 1.,
+            top_radius: 0.5,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, -2.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 1.5);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn calculate_solid_angle_bounds() {
+        let sdf = SDFPrimitive::SolidAngle {
+            angle: FRAC_PI_2 / 3.,
+            radius: 2.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, 0.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn calculates_plane_sdf() {
+        let sdf = SDFPrimitive::Plane {
+            normal: Vec3::new(1., 1., 0.),
+            offset: 1.,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let surface = sdf.value_at_point(&(Vec3::new(1., 1., 0.).normalize() + Vec3::Z * 5.));
+        let outside = sdf.value_at_point(&(Vec3::new(2., 2., 0.).normalize() * 1.5));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn calculate_axis_aligned_plane_bounds() {
+        let sdf = SDFPrimitive::Plane {
+            normal: Vec3::NEG_Y,
+            offset: 1.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_eq!(bounds.0.x, f32::NEG_INFINITY);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_eq!(bounds.0.z, f32::NEG_INFINITY);
+        assert_eq!(bounds.1.x, f32::INFINITY);
+        assert_eq!(bounds.1.y, f32::INFINITY);
+        assert_eq!(bounds.1.z, f32::INFINITY);
+    }
+
+    #[test]
+    fn calculate_plane_bounds() {
+        let sdf = SDFPrimitive::Plane {
+            normal: Vec3::new(1., 1., 0.),
+            offset: 1.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_eq!(bounds.0, Vec3::splat(f32::NEG_INFINITY));
+        assert_eq!(bounds.1, Vec3::splat(f32::INFINITY));
+    }
+
+    fn l_shape() -> SDFPrimitive2D {
+        SDFPrimitive2D::Polygon(vec![vec![
+            Vec2::new(0., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(2., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 2.),
+            Vec2::new(0., 2.),
+        ]])
+    }
+
+    #[test]
+    fn calculates_extrusion_sdf() {
+        let sdf = SDFPrimitive::Extrusion {
+            shape: l_shape(),
+            half_depth: 1.,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::new(0.5, 0.5, 0.));
+        let notch = sdf.value_at_point(&Vec3::new(1.5, 1.5, 0.));
+        let face = sdf.value_at_point(&Vec3::new(0.5, 1.5, 1.5));
+        let edge = sdf.value_at_point(&Vec3::new(-1., 0.5, 2.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(notch, 0.5);
+        assert_float_absolute_eq!(face, 0.5);
+        assert_float_absolute_eq!(edge, 2f32.sqrt());
+    }
+
+    #[test]
+    fn calculate_extrusion_bounds() {
+        let sdf = SDFPrimitive::Extrusion {
+            shape: l_shape(),
+            half_depth: 1.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, 0.);
+        assert_float_absolute_eq!(bounds.0.y, 0.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    fn vase() -> SDFPrimitive {
+        SDFPrimitive::Revolution(vec![
+            SDFPrimitive2D::Polygon(vec![vec![
+                Vec2::new(0., 0.),
+                Vec2::new(1., 0.),
+                Vec2::new(1., 2.),
+                Vec2::new(0., 2.),
+            ]]),
+            SDFPrimitive2D::Circle {
+                center: Vec2::new(0., 2.),
+                radius: 0.5,
+            },
+        ])
+    }
+
+    #[test]
+    fn calculates_revolution_sdf() {
+        let sdf = vase();
+
+        let interior = sdf.value_at_point(&(Vec3::Y * 0.5));
+        let side = sdf.value_at_point(&Vec3::new(0., 1., 1.5));
+        let diagonal = sdf.value_at_point(&Vec3::new(FRAC_1_SQRT_2, 1., FRAC_1_SQRT_2));
+        let top = sdf.value_at_point(&(Vec3::Y * 3.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(side, 0.5);
+        assert_float_absolute_eq!(diagonal, 0.);
+        assert_float_absolute_eq!(top, 0.5);
+    }
+
+    #[test]
+    fn calculate_revolution_bounds() {
+        let bounds = vase().get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, 0.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 2.5);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn calculates_octahedron_sdf() {
+        let sdf = SDFPrimitive::Octahedron(1.);
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let vertex = sdf.value_at_point(&Vec3::Y);
+        let outside = sdf.value_at_point(&Vec3::new(2., 0., 0.));
+        let face = sdf.value_at_point(&Vec3::ONE);
+
+        assert_float_absolute_eq!(interior, -1. / 3f32.sqrt());
+        assert_float_absolute_eq!(vertex, 0.);
+        assert_float_absolute_eq!(outside, 1.);
+        assert_float_absolute_eq!(face, 2. / 3f32.sqrt());
+    }
+
+    #[test]
+    fn calculates_tetrahedron_sdf() {
+        let sdf = SDFPrimitive::Tetrahedron(3.);
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let vertex = sdf.value_at_point(&Vec3::splat(3f32.sqrt()));
+        let face = sdf.value_at_point(&(Vec3::NEG_ONE.normalize() * 2.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(vertex, 0.);
+        assert_float_absolute_eq!(face, 1.);
+    }
+
+    #[test]
+    fn calculates_dodecahedron_sdf() {
+        let sdf = SDFPrimitive::Dodecahedron(2.);
+        let phi = (1. + 5f32.sqrt()) / 2.;
+        let scale = 2. / 3f32.sqrt();
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let corner = sdf.value_at_point(&(Vec3::ONE * scale));
+        let vertex = sdf.value_at_point(&(Vec3::new(0., 1. / phi, phi) * scale));
+        let face = sdf.value_at_point(&(Vec3::new(1., 0., phi).normalize() * 2.));
+
+        assert_float_absolute_eq!(interior, -1.589_309);
+        assert_float_absolute_eq!(corner, 0.);
+        assert_float_absolute_eq!(vertex, 0.);
+        assert_float_absolute_eq!(face, 0.410_691);
+    }
+
+    #[test]
+    fn calculates_icosahedron_sdf() {
+        let sdf = SDFPrimitive::Icosahedron(2.);
+        let phi = (1. + 5f32.sqrt()) / 2.;
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let vertex = sdf.value_at_point(&(Vec3::new(phi, 0., 1.).normalize() * 2.));
+        let face = sdf.value_at_point(&(Vec3::new(-1., 1., -1.).normalize() * 2.));
+
+        assert_float_absolute_eq!(interior, -1.589_309);
+        assert_float_absolute_eq!(vertex, 0.);
+        assert_float_absolute_eq!(face, 0.410_691);
+    }
+
+    #[test]
+    fn polyhedron_sdfs_are_lipschitz_bound() {
+        for sdf in [
+            SDFPrimitive::Octahedron(2.),
+            SDFPrimitive::Tetrahedron(2.),
+            SDFPrimitive::Dodecahedron(2.),
+            SDFPrimitive::Icosahedron(2.),
+            SDFPrimitive::HexagonalPrism {
+                radius: 1.5,
+                half_height: 1.,
+            },
+            SDFPrimitive::TriangularPrism {
+                radius: 1.,
+                half_height: 2.,
+            },
+            SDFPrimitive::SquarePyramid {
+                half_base: 1.5,
+                height: 2.,
+            },
+        ] {
+            for a in sample_points(9, Vec3::splat(0.9), Vec3::splat(-3.6)) {
+                for b in sample_points(9, Vec3::splat(0.9), Vec3::splat(-3.6)).step_by(7) {
+                    let difference = (sdf.value_at_point(&a) - sdf.value_at_point(&b)).abs();
+                    assert!(difference <= a.distance(b) + 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn calculates_hexagonal_prism_sdf() {
+        let sdf = SDFPrimitive::HexagonalPrism {
+            radius: 1.,
+            half_height: 2.,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let side = sdf.value_at_point(&(Vec3::Z * 1.5));
+        let corner = sdf.value_at_point(&(Vec3::X * 2. / 3f32.sqrt()));
+        let top = sdf.value_at_point(&(Vec3::Y * 3.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(side, 0.5);
+        assert_float_absolute_eq!(corner, 0.);
+        assert_float_absolute_eq!(top, 1.);
+    }
+
+    #[test]
+    fn calculates_triangular_prism_sdf() {
+        let sdf = SDFPrimitive::TriangularPrism {
+            radius: 1.,
+            half_height: 2.,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let side = sdf.value_at_point(&(Vec3::Z * -1.));
+        let corner = sdf.value_at_point(&(Vec3::Z * 2.));
+        let outside = sdf.value_at_point(&(Vec3::Z * 3.));
+        let top = sdf.value_at_point(&(Vec3::Y * 3.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(side, 0.);
+        assert_float_absolute_eq!(corner, 0.);
+        assert_float_absolute_eq!(outside, 1.);
+        assert_float_absolute_eq!(top, 1.);
+    }
+
+    #[test]
+    fn calculates_square_pyramid_sdf() {
+        let sdf = SDFPrimitive::SquarePyramid {
+            half_base: 1.,
+            height: 2.,
+        };
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let base = sdf.value_at_point(&(Vec3::Y * -0.9));
+        let tip = sdf.value_at_point(&Vec3::Y);
+        let below = sdf.value_at_point(&(Vec3::Y * -2.));
+        let above = sdf.value_at_point(&(Vec3::Y * 2.));
+
+        assert_float_absolute_eq!(interior, -1. / 5f32.sqrt());
+        assert_float_absolute_eq!(base, -0.1);
+        assert_float_absolute_eq!(tip, 0.);
+        assert_float_absolute_eq!(below, 1.);
+        assert_float_absolute_eq!(above, 1.);
+    }
+
+    #[test]
+    fn calculate_platonic_solid_bounds() {
+        let extents = [
+            (SDFPrimitive::Octahedron(2.), 2.),
+            (SDFPrimitive::Tetrahedron(2.), 1.154_701),
+            (SDFPrimitive::Dodecahedron(2.), 1.868_345),
+            (SDFPrimitive::Icosahedron(2.), 1.701_302),
+        ];
+        for (sdf, extent) in extents {
+            let bounds = sdf.get_bounds();
+            assert_float_absolute_eq!(bounds.0.x, -extent);
+            assert_float_absolute_eq!(bounds.0.y, -extent);
+            assert_float_absolute_eq!(bounds.0.z, -extent);
+            assert_float_absolute_eq!(bounds.1.x, extent);
+            assert_float_absolute_eq!(bounds.1.y, extent);
+            assert_float_absolute_eq!(bounds.1.z, extent);
+        }
+    }
+
+    #[test]
+    fn calculate_hexagonal_prism_bounds() {
+        let sdf = SDFPrimitive::HexagonalPrism {
+            radius: 1.,
+            half_height: 2.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -2. / 3f32.sqrt());
+        assert_float_absolute_eq!(bounds.0.y, -2.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 2. / 3f32.sqrt());
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    #[test]
+    fn calculate_triangular_prism_bounds() {
+        let sdf = SDFPrimitive::TriangularPrism {
+            radius: 1.,
+            half_height: 2.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -(3f32.sqrt()));
+        assert_float_absolute_eq!(bounds.0.y, -2.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 3f32.sqrt());
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 2.);
+    }
+
+    #[test]
+    fn calculate_square_pyramid_bounds() {
+        let sdf = SDFPrimitive::SquarePyramid {
+            half_base: 1.,
+            height: 2.,
+        };
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -1.);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_float_absolute_eq!(bounds.1.z, 1.);
+    }
+
+    struct Cube(f32);
+
+    impl CustomPrimitive for Cube {
+        fn distance(&self, point: Vec3) -> f32 {
+            (point.abs() - self.0).max_element()
+        }
+
+        fn bounds(&self) -> (Vec3, Vec3) {
+            (Vec3::splat(-self.0), Vec3::splat(self.0))
+        }
+    }
+
+    #[test]
+    fn calculates_custom_sdf() {
+        let sdf = SDFPrimitive::Custom(Arc::new(Cube(1.)));
+
+        let interior = sdf.value_at_point(&Vec3::ZERO);
+        let surface = sdf.value_at_point(&Vec3::Y);
+        let outside = sdf.value_at_point(&Vec3::new(1.5, 0., 0.));
+
+        assert_float_absolute_eq!(interior, -1.);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(outside, 0.5);
+    }
+
+    #[test]
+    fn compares_custom_primitives_by_instance() {
+        let cube: Arc<dyn CustomPrimitive> = Arc::new(Cube(1.));
+
+        assert_eq!(
+            SDFPrimitive::Custom(cube.clone()),
+            SDFPrimitive::Custom(cube)
+        );
+        assert_ne!(
+            SDFPrimitive::Custom(Arc::new(Cube(1.))),
+            SDFPrimitive::Custom(Arc::new(Cube(1.)))
+        );
+    }
+
+    #[test]
+    fn calculate_custom_bounds() {
+        let sdf = SDFPrimitive::Custom(Arc::new(Cube(2.)));
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -2.);
+        assert_float_absolute_eq!(bounds.0.y, -2.);
+        assert_float_absolute_eq!(bounds.0.z, -2.);
+        assert_float_absolute_eq!(bounds.1.x, 2.);
+        assert_float_absolute_eq!(bounds.1.y, 2.);
+        assert_float_absolute_eq!(bounds.1.z, 2.);
+    }
 }
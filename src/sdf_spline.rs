@@ -0,0 +1,346 @@
+//! Signed distance fields for tubes following splines
+use std::ops::{Add, Mul};
+
+use bevy::prelude::*;
+
+/// A tube following a chain of quadratic Bézier segments, with a radius at each control point
+///
+/// The distance is the smallest distance to a point on the curve, minus the radius at that point.
+/// It is exact for a constant radius, and never overestimates when the radius changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SDFSpline {
+    /// The segments of the spline
+    segments: Vec<BezierSegment>,
+}
+
+/// A single quadratic Bézier segment
+#[derive(Debug, Clone, PartialEq)]
+struct BezierSegment {
+    points: [Vec3; 3],
+    radii: [f32; 3],
+}
+
+impl SDFSpline {
+    /// Create a spline from a chain of quadratic Bézier segments
+    ///
+    /// The points alternate between points on the curve and control points, starting and
+    /// ending on the curve - so every segment shares it's end point with the next segment.
+    /// Returns `None` unless there is an odd number of at least 3 points, with a radius for each.
+    pub fn bezier(points: &[Vec3], radii: &[f32]) -> Option<Self> {
+        if points.len() < 3 || points.len() % 2 != 1 || points.len() != radii.len() {
+            return None;
+        }
+        let segments = (0..points.len() / 2)
+            .map(|segment| {
+                let start = segment * 2;
+                BezierSegment {
+                    points: [points[start], points[start + 1], points[start + 2]],
+                    radii: [radii[start], radii[start + 1], radii[start + 2]],
+                }
+            })
+            .collect();
+        Some(Self { segments })
+    }
+
+    /// Create a spline passing through every point, using Catmull-Rom tangents
+    ///
+    /// Each span is approximated by a single quadratic Bézier segment.
+    /// Returns `None` unless there are at least 2 points, with a radius for each.
+    pub fn catmull_rom(points: &[Vec3], radii: &[f32]) -> Option<Self> {
+        if points.len() < 2 || points.len() != radii.len() {
+            return None;
+        }
+        let last = points.len() - 1;
+        let tangent =
+            |index: usize| (points[(index + 1).min(last)] - points[index.saturating_sub(1)]) / 2.;
+        let segments = (0..last)
+            .map(|index| {
+                let start = points[index];
+                let end = points[index + 1];
+                let start_control = start + tangent(index) / 3.;
+                let end_control = end - tangent(index + 1) / 3.;
+                BezierSegment {
+                    points: [
+                        start,
+                        (3. * (start_control + end_control) - start - end) / 4.,
+                        end,
+                    ],
+                    radii: [
+                        radii[index],
+                        (radii[index] + radii[index + 1]) / 2.,
+                        radii[index + 1],
+                    ],
+                }
+            })
+            .collect();
+        Some(Self { segments })
+    }
+
+    /// Get the value of the SDF at a given point
+    pub fn value_at_point(&self, point: Vec3) -> f32 {
+        self.segments
+            .iter()
+            .map(|segment| segment.value_at_point(point))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Get the bounds of the spline, from the control points and the largest radius
+    pub fn get_bounds(&self) -> (Vec3, Vec3) {
+        let radius = self
+            .segments
+            .iter()
+            .flat_map(|segment| segment.radii)
+            .fold(0., f32::max);
+        let (min, max) = self
+            .segments
+            .iter()
+            .flat_map(|segment| segment.points)
+            .fold(
+                (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+                |(min, max), point| (min.min(point), max.max(point)),
+            );
+        (min - radius, max + radius)
+    }
+}
+
+impl BezierSegment {
+    fn radius(&self, t: f32) -> f32 {
+        quadratic_bezier(self.radii, t)
+    }
+
+    fn closest_point(&self, point: Vec3) -> (f32, f32) {
+        closest_point_on_quadratic(self.points, point)
+    }
+
+    /// Get the smallest value of `|C(t) - point| - radius(t)` along the segment
+    ///
+    /// With a changing radius, the parameter range is subdivided, and each interval is bounded
+    /// from below using the closest distance and the Lipschitz constant of the value. The result
+    /// is the lowest bound left once the intervals are within `SPLINE_TOLERANCE` of the best value
+    /// found, so it is at most that much below the true value and never above it.
+    fn value_at_point(&self, point: Vec3) -> f32 {
+        let (closest, distance) = self.closest_point(point);
+        let [r0, r1, r2] = self.radii;
+        let mut best = distance - self.radius(closest);
+        if r0 == r1 && r1 == r2 {
+            return best;
+        }
+
+        let [p0, p1, p2] = self.points;
+        let radius_slope = 2. * (r1 - r0).abs().max((r2 - r1).abs());
+        let lipschitz = 2. * (p1 - p0).length().max((p2 - p1).length()) + radius_slope;
+        let value = |t: f32| quadratic_bezier(self.points, t).distance(point) - self.radius(t);
+
+        let mut lowest = f32::INFINITY;
+        let start = (0., 1., value(0.), value(1.));
+        let mut intervals = vec![start];
+        while let Some((a, b, value_a, value_b)) = intervals.pop() {
+            best = best.min(value_a).min(value_b);
+            let half_width = (b - a) / 2.;
+            let largest_radius = (self.radius(a) + self.radius(b)) / 2. + radius_slope * half_width;
+            let bound =
+                ((value_a + value_b) / 2. - lipschitz * half_width).max(distance - largest_radius);
+            if bound >= best - SPLINE_TOLERANCE || half_width < SPLINE_MIN_INTERVAL {
+                lowest = lowest.min(bound);
+            } else {
+                let middle = a + half_width;
+                let value_middle = value(middle);
+                intervals.push((a, middle, value_a, value_middle));
+                intervals.push((middle, b, value_middle, value_b));
+            }
+        }
+        lowest.min(best)
+    }
+}
+
+/// How far below the true distance a spline with a changing radius may be
+const SPLINE_TOLERANCE: f32 = 1e-3;
+
+/// The smallest parameter interval subdivided when bounding a changing radius
+const SPLINE_MIN_INTERVAL: f32 = 1. / 4096.;
+
+/// Evaluate a quadratic Bézier curve
+pub(crate) fn quadratic_bezier<T>(points: [T; 3], t: f32) -> T
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    let [a, b, c] = points;
+    a * ((1. - t) * (1. - t)) + b * (2. * t * (1. - t)) + c * (t * t)
+}
+
+/// Find the parameter of the closest point on a quadratic Bézier curve, along with it's distance
+pub(crate) fn closest_point_on_quadratic(points: [Vec3; 3], point: Vec3) -> (f32, f32) {
+    let [start, control, end] = points;
+    let a = control - start;
+    let b = start - 2. * control + end;
+    let d = start - point;
+
+    let mut candidates = [0., 1., 0., 0.];
+    if b.length_squared() < 1e-10 {
+        let line = end - start;
+        if line.length_squared() > 0. {
+            candidates[2] = ((point - start).dot(line) / line.length_squared()).clamp(0., 1.);
+        }
+    } else {
+        // Solve the cubic for the points where the tangent is perpendicular to the offset
+        let kk = 1. / b.dot(b);
+        let kx = kk * a.dot(b);
+        let ky = kk * (2. * a.dot(a) + d.dot(b)) / 3.;
+        let kz = kk * d.dot(a);
+        let p = ky - kx * kx;
+        let q = kx * (2. * kx * kx - 3. * ky) + kz;
+        let h = q * q + 4. * p * p * p;
+        if h >= 0. {
+            let h = h.sqrt();
+            let t = ((h - q) / 2.).cbrt() + ((-h - q) / 2.).cbrt() - kx;
+            candidates[2] = t.clamp(0., 1.);
+        } else {
+            let z = (-p).sqrt();
+            let v = (q / (p * z * 2.)).clamp(-1., 1.).acos() / 3.;
+            let m = v.cos();
+            let n = v.sin() * 3f32.sqrt();
+            candidates[2] = ((m + m) * z - kx).clamp(0., 1.);
+            candidates[3] = ((-n - m) * z - kx).clamp(0., 1.);
+        }
+    }
+
+    candidates
+        .iter()
+        .map(|t| (*t, quadratic_bezier(points, *t).distance(point)))
+        .fold((0., f32::INFINITY), |closest, candidate| {
+            if candidate.1 < closest.1 {
+                candidate
+            } else {
+                closest
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use super::*;
+    use crate::utils::sample_points;
+
+    fn brute_force(spline: &SDFSpline, point: Vec3) -> f32 {
+        spline
+            .segments
+            .iter()
+            .flat_map(|segment| {
+                (0..=2000).map(move |i| {
+                    let t = i as f32 / 2000.;
+                    quadratic_bezier(segment.points, t).distance(point) - segment.radius(t)
+                })
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    #[test]
+    fn rejects_invalid_points() {
+        assert!(SDFSpline::bezier(&[Vec3::ZERO, Vec3::X], &[1., 1.]).is_none());
+        assert!(SDFSpline::bezier(&[Vec3::ZERO, Vec3::X, Vec3::Y], &[1., 1.]).is_none());
+        assert!(SDFSpline::catmull_rom(&[Vec3::ZERO], &[1.]).is_none());
+    }
+
+    #[test]
+    fn calculates_straight_tube_sdf() {
+        let sdf = SDFSpline::bezier(&[Vec3::ZERO, Vec3::X, Vec3::X * 2.], &[0.5; 3]).unwrap();
+
+        let interior = sdf.value_at_point(Vec3::X);
+        let surface = sdf.value_at_point(Vec3::new(1.5, 0.5, 0.));
+        let cap = sdf.value_at_point(Vec3::new(3., 0., 0.));
+
+        assert_float_absolute_eq!(interior, -0.5);
+        assert_float_absolute_eq!(surface, 0.);
+        assert_float_absolute_eq!(cap, 0.5);
+    }
+
+    #[test]
+    fn bezier_matches_brute_force() {
+        let sdf = SDFSpline::bezier(
+            &[
+                Vec3::new(-1., -1., 0.),
+                Vec3::new(0., 2., 0.5),
+                Vec3::new(1., -1., 0.),
+                Vec3::new(2., -2., -1.),
+                Vec3::new(1., 1., 1.),
+            ],
+            &[0.2; 5],
+        )
+        .unwrap();
+
+        for point in sample_points(6, Vec3::splat(0.7), Vec3::splat(-1.3)) {
+            assert_float_absolute_eq!(sdf.value_at_point(point), brute_force(&sdf, point), 1e-3);
+        }
+    }
+
+    #[test]
+    fn varying_radius_never_overestimates() {
+        let sdf = SDFSpline::bezier(
+            &[Vec3::new(-1., 0., 0.), Vec3::Y, Vec3::new(1., 0., 0.)],
+            &[0.1, 0.3, 0.5],
+        )
+        .unwrap();
+
+        let top = sdf.value_at_point(Vec3::Y);
+        let end = sdf.value_at_point(Vec3::new(2., 0., 0.));
+
+        assert_float_absolute_eq!(top, brute_force(&sdf, Vec3::Y), 2e-3);
+        assert_float_absolute_eq!(end, 0.5, 2e-3);
+        for point in sample_points(6, Vec3::splat(0.7), Vec3::splat(-1.3)) {
+            let value = sdf.value_at_point(point);
+            let expected = brute_force(&sdf, point);
+            assert!(value <= expected + 1e-5);
+            assert_float_absolute_eq!(value, expected, 2e-3);
+        }
+    }
+
+    #[test]
+    fn growing_radius_uses_the_closest_surface() {
+        let sdf =
+            SDFSpline::bezier(&[Vec3::ZERO, Vec3::X, Vec3::X * 2.], &[0.1, 0.55, 1.]).unwrap();
+
+        let point = Vec3::new(0., 1.5, 0.);
+        let value = sdf.value_at_point(point);
+        let expected = brute_force(&sdf, point);
+
+        assert!(value <= expected + 1e-5);
+        assert_float_absolute_eq!(value, expected, 2e-3);
+        assert!(value < 1.3);
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_points() {
+        let points = [
+            Vec3::ZERO,
+            Vec3::new(1., 1., 0.),
+            Vec3::new(2., 0., 1.),
+            Vec3::new(3., 1., 1.),
+        ];
+        let sdf = SDFSpline::catmull_rom(&points, &[0.25; 4]).unwrap();
+
+        for point in points {
+            assert_float_absolute_eq!(sdf.value_at_point(point), -0.25, 1e-5);
+        }
+        for point in sample_points(6, Vec3::splat(0.7), Vec3::splat(-1.3)) {
+            assert_float_absolute_eq!(sdf.value_at_point(point), brute_force(&sdf, point), 1e-3);
+        }
+    }
+
+    #[test]
+    fn calculates_spline_bounds() {
+        let sdf = SDFSpline::bezier(
+            &[Vec3::new(-1., 0., 0.), Vec3::new(0., 2., 1.), Vec3::X],
+            &[0.1, 0.5, 0.2],
+        )
+        .unwrap();
+        let bounds = sdf.get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.5);
+        assert_float_absolute_eq!(bounds.0.y, -0.5);
+        assert_float_absolute_eq!(bounds.0.z, -0.5);
+        assert_float_absolute_eq!(bounds.1.x, 1.5);
+        assert_float_absolute_eq!(bounds.1.y, 2.5);
+        assert_float_absolute_eq!(bounds.1.z, 1.5);
+    }
+}
@@ -0,0 +1,348 @@
+//! Signed distance fields for text, extruded from font outlines
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use ab_glyph::{Font as _, OutlineCurve, Point};
+use bevy::{math::Vec3Swizzles, prelude::*, text::Font};
+
+use crate::sdf_spline::{closest_point_on_quadratic, quadratic_bezier};
+
+/// A line of text, extruded from the outlines of a font's glyphs
+///
+/// The text starts at the origin and runs along the X axis, with it's baseline on the X axis
+/// and following lines below it. The outlines are extruded along the Z axis,
+/// centered on the origin.
+///
+/// Distances to the outlines are exact, except for cubic curves - which get approximated
+/// by quadratic ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SDFText {
+    /// The outlines of each glyph
+    glyphs: Vec<GlyphOutline>,
+    /// Half of the depth of the extrusion
+    half_depth: f32,
+    /// The size of the chamfer on the edges of the glyphs
+    bevel: f32,
+}
+
+/// The outline of a single glyph, as quadratic Bézier curves
+#[derive(Debug, Clone, PartialEq)]
+struct GlyphOutline {
+    curves: Vec<[Vec2; 3]>,
+    min: Vec2,
+    max: Vec2,
+}
+
+impl SDFText {
+    /// Lay out a string using a font, with the given size in world units
+    ///
+    /// The depth defaults to a fifth of the size, with no bevel.
+    /// Returns `None` if the text has no visible glyphs.
+    pub fn new(font: &Font, text: &str, size: f32) -> Option<Self> {
+        let font = &font.font;
+        let scale = size / font.units_per_em()?;
+        let line_height = font.height_unscaled() + font.line_gap_unscaled();
+
+        let mut caret = Vec2::ZERO;
+        let mut previous = None;
+        let mut glyphs = vec![];
+        for character in text.chars() {
+            if character == '\n' {
+                caret = Vec2::new(0., caret.y - line_height);
+                previous = None;
+                continue;
+            }
+            let id = font.glyph_id(character);
+            if let Some(previous) = previous {
+                caret.x += font.kern_unscaled(previous, id);
+            }
+            if let Some(outline) = font.outline(id) {
+                glyphs.extend(GlyphOutline::new(outline.curves, caret, scale));
+            }
+            caret.x += font.h_advance_unscaled(id);
+            previous = Some(id);
+        }
+
+        if glyphs.is_empty() {
+            return None;
+        }
+        Some(Self {
+            glyphs,
+            half_depth: size / 10.,
+            bevel: 0.,
+        })
+    }
+
+    /// Set the depth of the extrusion
+    pub fn with_depth(mut self, depth: f32) -> Self {
+        self.half_depth = depth / 2.;
+        self
+    }
+
+    /// Set the size of the chamfer on the front and back edges of the glyphs
+    pub fn with_bevel(mut self, bevel: f32) -> Self {
+        self.bevel = bevel;
+        self
+    }
+
+    /// Get the value of the 2D SDF of the outlines at a given point on the XY plane
+    fn outline_value_at_point(&self, point: Vec2) -> f32 {
+        let mut distance = f32::INFINITY;
+        let mut winding = 0;
+        for glyph in self.glyphs.iter() {
+            let clamped = point.clamp(glyph.min, glyph.max);
+            if clamped == point {
+                winding += glyph.winding(point);
+            } else if point.distance(clamped) >= distance {
+                continue;
+            }
+            distance = distance.min(glyph.distance(point));
+        }
+        if winding == 0 {
+            distance
+        } else {
+            -distance
+        }
+    }
+
+    /// Get the value of the SDF at a given point
+    pub fn value_at_point(&self, point: Vec3) -> f32 {
+        let q = Vec2::new(
+            self.outline_value_at_point(point.xy()),
+            point.z.abs() - self.half_depth,
+        );
+        let extrusion = q.max(Vec2::ZERO).length() + q.max_element().min(0.);
+        extrusion.max((q.x + q.y + self.bevel) * FRAC_1_SQRT_2)
+    }
+
+    /// Get the bounds of the text
+    pub fn get_bounds(&self) -> (Vec3, Vec3) {
+        let (min, max) = self.glyphs.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), glyph| (min.min(glyph.min), max.max(glyph.max)),
+        );
+        (min.extend(-self.half_depth), max.extend(self.half_depth))
+    }
+}
+
+impl GlyphOutline {
+    /// Convert a glyph's curves into quadratic curves, offset and scaled into world units
+    ///
+    /// Returns `None` if the glyph has no curves
+    fn new(curves: Vec<OutlineCurve>, offset: Vec2, scale: f32) -> Option<Self> {
+        let position = |point: Point| (Vec2::new(point.x, point.y) + offset) * scale;
+        let curves = curves
+            .into_iter()
+            .flat_map(|curve| match curve {
+                OutlineCurve::Line(a, b) => {
+                    let (a, b) = (position(a), position(b));
+                    vec![[a, (a + b) / 2., b]]
+                }
+                OutlineCurve::Quad(a, b, c) => vec![[position(a), position(b), position(c)]],
+                OutlineCurve::Cubic(a, b, c, d) => {
+                    split_cubic([position(a), position(b), position(c), position(d)])
+                }
+            })
+            .collect::<Vec<_>>();
+        if curves.is_empty() {
+            return None;
+        }
+        let (min, max) = curves.iter().flatten().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), point| (min.min(*point), max.max(*point)),
+        );
+        Some(Self { curves, min, max })
+    }
+
+    fn distance(&self, point: Vec2) -> f32 {
+        self.curves
+            .iter()
+            .map(|curve| {
+                closest_point_on_quadratic(curve.map(|p| p.extend(0.)), point.extend(0.)).1
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// The number of times the outline winds around a point, following the non-zero rule
+    fn winding(&self, point: Vec2) -> i32 {
+        let mut winding = 0;
+        for curve in self.curves.iter() {
+            // Split the curve where it turns around vertically,
+            // so each part crosses the point's height at most once
+            let [a, b, c] = *curve;
+            let denominator = a.y - 2. * b.y + c.y;
+            let turn = if denominator.abs() > 1e-12 {
+                (a.y - b.y) / denominator
+            } else {
+                -1.
+            };
+            let parts = if turn > 0. && turn < 1. {
+                vec![(0., turn), (turn, 1.)]
+            } else {
+                vec![(0., 1.)]
+            };
+
+            for (start, end) in parts {
+                let below = quadratic_bezier(*curve, start).y <= point.y;
+                let rising = quadratic_bezier(*curve, end).y > quadratic_bezier(*curve, start).y;
+                if below == (quadratic_bezier(*curve, end).y <= point.y) {
+                    continue;
+                }
+                let (mut low, mut high) = (start, end);
+                for _ in 0..24 {
+                    let middle = (low + high) / 2.;
+                    if (quadratic_bezier(*curve, middle).y <= point.y) == below {
+                        low = middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                if quadratic_bezier(*curve, (low + high) / 2.).x > point.x {
+                    winding += if rising { 1 } else { -1 };
+                }
+            }
+        }
+        winding
+    }
+}
+
+/// Approximate a cubic Bézier curve with two quadratic ones, split at it's midpoint
+fn split_cubic(points: [Vec2; 4]) -> Vec<[Vec2; 3]> {
+    let [a, b, c, d] = points;
+    let ab = (a + b) / 2.;
+    let bc = (b + c) / 2.;
+    let cd = (c + d) / 2.;
+    let abc = (ab + bc) / 2.;
+    let bcd = (bc + cd) / 2.;
+    let middle = (abc + bcd) / 2.;
+    [[a, ab, abc, middle], [middle, bcd, cd, d]]
+        .iter()
+        .map(|[a, b, c, d]| [*a, (3. * (*b + *c) - *a - *d) / 4., *d])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ab_glyph::point;
+    use assert_float_eq::*;
+
+    use super::*;
+    use crate::{
+        sdf_object::{SDFElement, SDFObject},
+        sdf_operations::SDFOperators,
+        sdf_primitives::SDFPrimitive,
+    };
+
+    /// A square "O", 2 units wide with a 1 unit hole - wound backwards, like in a font
+    fn square_ring() -> SDFText {
+        let ring = |size: f32, clockwise: bool| {
+            let mut corners = [
+                point(-size, -size),
+                point(size, -size),
+                point(size, size),
+                point(-size, size),
+            ];
+            if clockwise {
+                corners.reverse();
+            }
+            (0..4)
+                .map(|i| OutlineCurve::Line(corners[i], corners[(i + 1) % 4]))
+                .collect::<Vec<_>>()
+        };
+        let curves = ring(1., false).into_iter().chain(ring(0.5, true)).collect();
+        SDFText {
+            glyphs: GlyphOutline::new(curves, Vec2::ZERO, 1.)
+                .into_iter()
+                .collect(),
+            half_depth: 0.25,
+            bevel: 0.,
+        }
+    }
+
+    #[test]
+    fn calculates_glyph_outline_sdf() {
+        let sdf = square_ring();
+
+        assert_float_absolute_eq!(sdf.outline_value_at_point(Vec2::new(0.75, 0.)), -0.25);
+        assert_float_absolute_eq!(sdf.outline_value_at_point(Vec2::ZERO), 0.5);
+        assert_float_absolute_eq!(sdf.outline_value_at_point(Vec2::new(2., 0.)), 1.);
+        assert_float_absolute_eq!(sdf.outline_value_at_point(Vec2::new(0., 1.)), 0.);
+    }
+
+    #[test]
+    fn calculates_quadratic_glyph_sdf() {
+        let curves = vec![
+            OutlineCurve::Quad(point(-1., 0.), point(0., 2.), point(1., 0.)),
+            OutlineCurve::Line(point(1., 0.), point(-1., 0.)),
+        ];
+        let sdf = SDFText {
+            glyphs: GlyphOutline::new(curves, Vec2::ZERO, 1.)
+                .into_iter()
+                .collect(),
+            half_depth: 1.,
+            bevel: 0.,
+        };
+
+        assert_float_absolute_eq!(sdf.outline_value_at_point(Vec2::new(0., 0.5)), -0.5);
+        assert_float_absolute_eq!(sdf.outline_value_at_point(Vec2::new(0., 2.)), 1.);
+        assert_float_absolute_eq!(sdf.outline_value_at_point(Vec2::new(0., -0.5)), 0.5);
+    }
+
+    #[test]
+    fn approximates_cubic_curves() {
+        let curves = split_cubic([
+            Vec2::new(-1., 0.),
+            Vec2::new(-1., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 0.),
+        ]);
+
+        assert_eq!(curves.len(), 2);
+        assert_float_absolute_eq!(curves[0][2].x, 0.);
+        assert_float_absolute_eq!(curves[0][2].y, 0.75);
+        assert_float_absolute_eq!(curves[1][2].x, 1.);
+    }
+
+    #[test]
+    fn extrudes_and_bevels_glyphs() {
+        let sdf = square_ring();
+
+        let interior = sdf.value_at_point(Vec3::new(0.75, 0., 0.));
+        let front = sdf.value_at_point(Vec3::new(0.75, 0., 1.));
+        let edge = sdf.value_at_point(Vec3::new(1., 0., 0.25));
+        let bevelled = sdf.with_bevel(0.1).value_at_point(Vec3::new(1., 0., 0.25));
+
+        assert_float_absolute_eq!(interior, -0.25);
+        assert_float_absolute_eq!(front, 0.75);
+        assert_float_absolute_eq!(edge, 0.);
+        assert_float_absolute_eq!(bevelled, 0.1 * FRAC_1_SQRT_2);
+    }
+
+    #[test]
+    fn engraves_text_into_a_box() {
+        let object = SDFObject::default()
+            .with_element(SDFElement::default().with_primitive(SDFPrimitive::Box(Vec3::ONE * 2.)))
+            .with_element(
+                SDFElement::default()
+                    .with_primitive(SDFPrimitive::Text(Arc::new(square_ring())))
+                    .with_translation(Vec3::Z * 2.)
+                    .with_operation(SDFOperators::Subtraction),
+            );
+
+        assert_float_absolute_eq!(object.value_at_point(&Vec3::new(0.75, 0., 2.)), 0.25);
+        assert_float_absolute_eq!(object.value_at_point(&Vec3::new(0., 0., 2.)), 0.);
+        assert_float_absolute_eq!(object.value_at_point(&Vec3::new(0.75, 0., 1.5)), -0.25);
+    }
+
+    #[test]
+    fn calculates_text_bounds() {
+        let bounds = square_ring().with_depth(1.).get_bounds();
+        assert_float_absolute_eq!(bounds.0.x, -1.);
+        assert_float_absolute_eq!(bounds.0.y, -1.);
+        assert_float_absolute_eq!(bounds.0.z, -0.5);
+        assert_float_absolute_eq!(bounds.1.x, 1.);
+        assert_float_absolute_eq!(bounds.1.y, 1.);
+        assert_float_absolute_eq!(bounds.1.z, 0.5);
+    }
+}
@@ -1,5 +1,8 @@
 //! A module
 
+#[cfg(test)]
+use bevy::prelude::Vec3;
+
 /// Returns true!
 ///
 /// A useless function used for testing that CI works.
@@ -13,6 +16,17 @@ pub fn returns_true() -> bool {
     true
 }
 
+/// A `count` by `count` by `count` grid of points used to sample SDFs in tests,
+/// with the given spacing along each axis, starting at the offset
+#[cfg(test)]
+pub(crate) fn sample_points(count: u32, step: Vec3, offset: Vec3) -> impl Iterator<Item = Vec3> {
+    (0..count).flat_map(move |x| {
+        (0..count).flat_map(move |y| {
+            (0..count).map(move |z| Vec3::new(x as f32, y as f32, z as f32) * step + offset)
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;